@@ -0,0 +1,10 @@
+#!/bin/sh
+# Seed each parse_dayN target's corpus with the puzzle and example inputs
+set -e
+cd "$(dirname "$0")"
+for input in ../input/day*; do
+    name=$(basename "$input")
+    day=${name%_ex}
+    mkdir -p "corpus/parse_$day"
+    cp "$input" "corpus/parse_$day/$name"
+done
@@ -0,0 +1,121 @@
+use std::{collections::BTreeMap, path};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+pub const DEFAULT_ANSWERS_FILE: &str = "answers.toml";
+
+/// Verified answers, keyed by day, then by the input file that produced them
+///
+/// ```toml
+/// [day1."input/day1"]
+/// part1 = "71502"
+/// part2 = "208191"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct AnswerStore {
+    days: BTreeMap<String, BTreeMap<String, PartAnswers>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PartAnswers {
+    part1: Option<String>,
+    part2: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::Fail { .. } => write!(f, "FAIL"),
+            Verdict::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+impl AnswerStore {
+    pub fn load<P: AsRef<path::Path>>(path: P) -> Result<AnswerStore> {
+        let content = super::read_to_string(&path)?;
+        Self::parse(&content)
+            .with_context(|| format!("could not parse answers: {}", path.as_ref().display()))
+    }
+
+    fn parse(content: &str) -> Result<AnswerStore> {
+        Ok(toml::from_str(content)?)
+    }
+
+    pub fn get(&self, day: u32, part: u32, input: &path::Path) -> Option<&str> {
+        let answers = self
+            .days
+            .get(&format!("day{}", day))?
+            .get(&input_key(input))?;
+        match part {
+            1 => answers.part1.as_deref(),
+            2 => answers.part2.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Compare an answer against the store. Input that did not come from a file
+    /// (e.g. stdin) can never be verified.
+    pub fn check(&self, day: u32, part: u32, input: Option<&path::Path>, answer: &str) -> Verdict {
+        match input.and_then(|i| self.get(day, part, i)) {
+            Some(expected) if expected.trim_end() == answer.trim_end() => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+fn input_key(input: &path::Path) -> String {
+    let key = input.to_string_lossy();
+    key.strip_prefix("./").unwrap_or(&key).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STORE: &str = r#"
+[day1."input/day1"]
+part1 = "71502"
+
+[day10."input/day10_ex"]
+part1 = "13140"
+part2 = """
+##..
+"""
+"#;
+
+    #[test]
+    fn check_answers() {
+        let store = AnswerStore::parse(STORE).unwrap();
+        let input = path::Path::new("input/day1");
+        assert_eq!(store.check(1, 1, Some(input), "71502"), Verdict::Pass);
+        assert_eq!(
+            store.check(1, 1, Some(input), "71501"),
+            Verdict::Fail {
+                expected: "71502".to_string()
+            }
+        );
+        assert_eq!(store.check(1, 2, Some(input), "1"), Verdict::Unknown);
+        assert_eq!(store.check(1, 1, None, "71502"), Verdict::Unknown);
+    }
+
+    #[test]
+    fn multiline_answers() {
+        let store = AnswerStore::parse(STORE).unwrap();
+        let input = path::Path::new("./input/day10_ex");
+        assert_eq!(store.check(10, 2, Some(input), "##..\n"), Verdict::Pass);
+    }
+}
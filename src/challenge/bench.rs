@@ -0,0 +1,242 @@
+use std::{path, time};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{RunContext, Solution};
+
+/// Summary of repeated runs of a single day/part, also used as the saved baseline format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchStats {
+    pub day: u32,
+    pub part: u32,
+    pub runs: usize,
+    pub min_secs: f64,
+    pub median_secs: f64,
+    pub p95_secs: f64,
+    /// Time taken to parse the input once, which the samples don't include
+    #[serde(default)]
+    pub parse_secs: f64,
+    /// SHA-256 of the measured input, so baselines are only compared on the same input
+    #[serde(default)]
+    pub digest: Option<String>,
+}
+
+impl BenchStats {
+    fn from_samples(
+        day: u32,
+        part: u32,
+        parse: time::Duration,
+        samples: &mut [time::Duration],
+    ) -> BenchStats {
+        samples.sort();
+        BenchStats {
+            day,
+            part,
+            runs: samples.len(),
+            min_secs: percentile(samples, 0.0).as_secs_f64(),
+            median_secs: percentile(samples, 50.0).as_secs_f64(),
+            p95_secs: percentile(samples, 95.0).as_secs_f64(),
+            parse_secs: parse.as_secs_f64(),
+            digest: None,
+        }
+    }
+
+    /// Refuse baselines measured for another day/part or on another input
+    pub fn check_comparable(&self, baseline: &BenchStats) -> Result<()> {
+        if (baseline.day, baseline.part) != (self.day, self.part) {
+            anyhow::bail!(
+                "baseline is for day {} part {}",
+                baseline.day,
+                baseline.part
+            )
+        }
+        match &baseline.digest {
+            Some(digest) if Some(digest) != self.digest.as_ref() => {
+                anyhow::bail!("baseline was measured on a different input")
+            }
+            Some(_) => {}
+            None => log::warn!("baseline does not record which input it measured"),
+        }
+        Ok(())
+    }
+
+    /// Relative change of the median against a baseline, e.g. `0.1` is 10% slower.
+    /// `None` when the baseline median is zero, so there is nothing to compare to.
+    pub fn median_change(&self, baseline: &BenchStats) -> Option<f64> {
+        if baseline.median_secs <= 0.0 {
+            return None;
+        }
+        Some((self.median_secs - baseline.median_secs) / baseline.median_secs)
+    }
+}
+
+impl std::fmt::Display for BenchStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let d = time::Duration::from_secs_f64;
+        write!(
+            f,
+            "day {} part {}: {} runs, min {:.2?}, median {:.2?}, p95 {:.2?}, parse {:.2?}",
+            self.day,
+            self.part,
+            self.runs,
+            d(self.min_secs),
+            d(self.median_secs),
+            d(self.p95_secs),
+            d(self.parse_secs)
+        )
+    }
+}
+
+/// Nearest-rank percentile of already sorted samples
+fn percentile(sorted: &[time::Duration], p: f64) -> time::Duration {
+    if sorted.is_empty() {
+        return time::Duration::ZERO;
+    }
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+pub fn bench(
+    s: &dyn Solution,
+    part: u32,
+    input: &str,
+    ctx: &RunContext,
+    warmup: usize,
+    runs: usize,
+) -> Result<BenchStats> {
+    let start = time::Instant::now();
+    let parsed = s.parse(input)?;
+    let parse = start.elapsed();
+
+    for _ in 0..warmup {
+        s.solve_parsed(part, &parsed, ctx)?;
+    }
+    let mut samples = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let start = time::Instant::now();
+        s.solve_parsed(part, &parsed, ctx)?;
+        samples.push(start.elapsed());
+    }
+    let mut stats = BenchStats::from_samples(s.day(), part, parse, &mut samples);
+    stats.digest = Some(super::history::digest(input));
+    Ok(stats)
+}
+
+pub fn run_bench(args: &clap::ArgMatches) -> Result<()> {
+    let day = args.value_of("day").unwrap().parse::<u32>()?;
+    let part = args.value_of("part").unwrap().parse::<u32>()?;
+    let runs = args.value_of("runs").unwrap().parse::<usize>()?;
+    let warmup = args.value_of("warmup").unwrap().parse::<usize>()?;
+    let threshold = args.value_of("threshold").unwrap().parse::<f64>()? / 100.0;
+    if runs == 0 {
+        anyhow::bail!("at least one run is required")
+    }
+
+    let solution = super::get_solution(day)
+        .ok_or_else(|| anyhow::anyhow!("unimplemented challenge day {} part {}", day, part))?;
+    let source = super::input_source(args.value_of("input"), day, args.is_present("example"));
+    let input = super::read_input(source.as_deref())?;
+    let ctx = super::run_context(solution, source.as_deref(), args.values_of("param"))?;
+
+    let stats = bench(solution, part, &input, &ctx, warmup, runs)?;
+    println!("{}", stats);
+
+    if let Some(path) = args.value_of("save_baseline") {
+        save_baseline(path, &stats)?;
+    }
+
+    if let Some(path) = args.value_of("baseline") {
+        let baseline = load_baseline(path)?;
+        stats
+            .check_comparable(&baseline)
+            .with_context(|| format!("can not compare against baseline {}", path))?;
+        println!("baseline {}", baseline);
+        let change = match stats.median_change(&baseline) {
+            Some(change) => change,
+            None => {
+                println!("baseline median is zero, nothing to compare against");
+                return Ok(());
+            }
+        };
+        println!("median change: {:+.1}%", change * 100.0);
+        if change > threshold {
+            anyhow::bail!(
+                "regression: median is {:.1}% slower than baseline (threshold {:.1}%)",
+                change * 100.0,
+                threshold * 100.0
+            )
+        }
+    }
+    Ok(())
+}
+
+fn save_baseline<P: AsRef<path::Path>>(path: P, stats: &BenchStats) -> Result<()> {
+    let content = serde_json::to_string_pretty(stats)?;
+    std::fs::write(&path, content)
+        .with_context(|| format!("Unable to write baseline: {}", path.as_ref().display()))
+}
+
+fn load_baseline<P: AsRef<path::Path>>(path: P) -> Result<BenchStats> {
+    let content = super::read_to_string(&path)?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("could not parse baseline: {}", path.as_ref().display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(x: u64) -> time::Duration {
+        time::Duration::from_millis(x)
+    }
+
+    #[test]
+    fn percentiles() {
+        let samples = (1..=20).map(ms).collect::<Vec<_>>();
+        assert_eq!(percentile(&samples, 0.0), ms(1));
+        assert_eq!(percentile(&samples, 50.0), ms(10));
+        assert_eq!(percentile(&samples, 95.0), ms(19));
+        assert_eq!(percentile(&samples, 100.0), ms(20));
+        assert_eq!(percentile(&[], 50.0), time::Duration::ZERO);
+    }
+
+    #[test]
+    fn stats_from_unsorted_samples() {
+        let mut samples = vec![ms(3), ms(1), ms(2)];
+        let stats = BenchStats::from_samples(14, 2, ms(5), &mut samples);
+        assert_eq!(stats.runs, 3);
+        assert_eq!(stats.min_secs, 0.001);
+        assert_eq!(stats.median_secs, 0.002);
+        assert_eq!(stats.p95_secs, 0.003);
+        assert_eq!(stats.parse_secs, 0.005);
+    }
+
+    #[test]
+    fn baselines_for_other_inputs() {
+        let day = crate::challenge::get_solution(1).unwrap();
+        let ctx = RunContext::default();
+        let stats = bench(day, 1, "1\n\n2\n", &ctx, 0, 1).unwrap();
+        let same = bench(day, 1, "1\n\n2\n", &ctx, 0, 1).unwrap();
+        assert!(stats.check_comparable(&same).is_ok());
+        let other = bench(day, 1, "3\n", &ctx, 0, 1).unwrap();
+        assert!(stats.check_comparable(&other).is_err());
+        let other_part = bench(day, 2, "1\n\n2\n", &ctx, 0, 1).unwrap();
+        assert!(stats.check_comparable(&other_part).is_err());
+
+        let old: BenchStats = serde_json::from_str(
+            r#"{"day":1,"part":1,"runs":1,"min_secs":0.1,"median_secs":0.1,"p95_secs":0.1}"#,
+        )
+        .unwrap();
+        assert!(stats.check_comparable(&old).is_ok());
+    }
+
+    #[test]
+    fn median_change_against_baseline() {
+        let baseline = BenchStats::from_samples(1, 1, ms(1), &mut [ms(10)]);
+        let current = BenchStats::from_samples(1, 1, ms(1), &mut [ms(12)]);
+        assert!((current.median_change(&baseline).unwrap() - 0.2).abs() < 1e-9);
+        let zero = BenchStats::from_samples(1, 1, ms(1), &mut [ms(0)]);
+        assert_eq!(current.median_change(&zero), None);
+    }
+}
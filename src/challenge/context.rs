@@ -0,0 +1,96 @@
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use anyhow::{Context, Result};
+
+/// A tunable value a day reads from the `RunContext`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Param {
+    pub name: &'static str,
+    pub help: &'static str,
+}
+
+/// What the runner knows about a run, beyond the puzzle input itself
+///
+/// Days read tunable values (rows, rounds, sizes) through `param`, so that
+/// examples can use the smaller values from the puzzle text and any value can
+/// be overridden by the caller.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunContext {
+    /// The input is one of the puzzle's examples
+    pub example: bool,
+    params: BTreeMap<String, String>,
+}
+
+impl RunContext {
+    pub fn new(example: bool) -> RunContext {
+        RunContext {
+            example,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_param(mut self, key: impl Into<String>, value: impl Into<String>) -> RunContext {
+        self.params.insert(key.into(), value.into());
+        self
+    }
+
+    /// Value of the parameter `key`, or `default` when it was not overridden
+    pub fn param<T>(&self, key: &str, default: T) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        match self.params.get(key) {
+            Some(value) => value
+                .parse::<T>()
+                .map_err(|e| anyhow::anyhow!("{}", e))
+                .with_context(|| format!("invalid value for parameter {}: {:?}", key, value)),
+            None => Ok(default),
+        }
+    }
+
+    /// Comma separated list value of the parameter `key`, or `default`
+    pub fn param_list<T>(&self, key: &str, default: &[T]) -> Result<Vec<T>>
+    where
+        T: FromStr + Clone,
+        T::Err: Display,
+    {
+        match self.params.get(key) {
+            Some(value) => value
+                .split(',')
+                .map(|v| v.trim().parse::<T>().map_err(|e| anyhow::anyhow!("{}", e)))
+                .collect::<Result<Vec<_>>>()
+                .with_context(|| format!("invalid value for parameter {}: {:?}", key, value)),
+            None => Ok(default.to_vec()),
+        }
+    }
+
+    /// Names of the parameters that were overridden
+    pub fn param_names(&self) -> impl Iterator<Item = &str> {
+        self.params.keys().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn params_fall_back_to_default() {
+        let ctx = RunContext::new(true).with_param("row", "10");
+        assert!(ctx.example);
+        assert_eq!(ctx.param("row", 2_000_000).unwrap(), 10);
+        assert_eq!(ctx.param("max_coord", 20).unwrap(), 20);
+        assert!(ctx.with_param("row", "ten").param("row", 0).is_err());
+    }
+
+    #[test]
+    fn list_params() {
+        let ctx = RunContext::default().with_param("signals", "1, 2,3");
+        assert_eq!(ctx.param_list("signals", &[20]).unwrap(), vec![1, 2, 3]);
+        assert_eq!(ctx.param_list("other", &[20]).unwrap(), vec![20]);
+        assert!(ctx.param_list::<u32>("signals", &[]).is_ok());
+        let ctx = ctx.with_param("signals", "1,,2");
+        assert!(ctx.param_list::<u32>("signals", &[]).is_err());
+    }
+}
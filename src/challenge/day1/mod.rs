@@ -2,26 +2,62 @@ use std::thread::current;
 
 use anyhow::{Context, Result};
 
-pub fn part1(input: &str) -> Result<String> {
-    let content = parse_input(input)?;
-    log::debug!("{:?}", content);
-    let largest = content
+use super::{
+    parser::{each_record, integer, lines},
+    Answer, Day, Example, Expected, RunContext,
+};
+
+pub struct Day1;
+
+impl Day for Day1 {
+    type Input = Vec<Vec<i64>>;
+
+    fn name(&self) -> &'static str {
+        "Calorie Counting"
+    }
+    fn day(&self) -> u32 {
+        1
+    }
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+    fn solve_p1(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        solve_p1(input).map(Answer::from)
+    }
+    fn solve_p2(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        solve_p2(input).map(Answer::from)
+    }
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../../input/day1_ex"),
+            expected: Expected {
+                part1: Some("24000"),
+                part2: Some("45000"),
+            },
+        }]
+    }
+
+    fn generate(&self, rng: &mut fastrand::Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
+}
+
+pub fn solve_p1(elves: &[Vec<i64>]) -> Result<i64> {
+    log::debug!("{:?}", elves);
+    elves
         .iter()
         .map(|elf| elf.iter().sum::<i64>())
         .max()
-        .unwrap();
-    Ok(format!("{:?}", largest))
+        .ok_or_else(|| anyhow::anyhow!("no elves in input"))
 }
 
-pub fn part2(input: &str) -> Result<String> {
-    let content = parse_input(input)?;
-    let mut totals = content
+pub fn solve_p2(elves: &[Vec<i64>]) -> Result<i64> {
+    let mut totals = elves
         .iter()
         .map(|elf| elf.iter().sum::<i64>())
         .collect::<Vec<_>>();
     totals.sort();
-    let top = totals.iter().rev().take(3).sum::<i64>();
-    Ok(format!("{:?}", top))
+    Ok(totals.iter().rev().take(3).sum::<i64>())
 }
 
 fn parse(input: &str) -> Result<Vec<i64>> {
@@ -34,50 +70,53 @@ fn parse(input: &str) -> Result<Vec<i64>> {
         .collect()
 }
 
-fn parse_input(input: &str) -> Result<Vec<Vec<i64>>> {
-    let mut elves = Vec::new();
-    let mut current = Vec::new();
-
-    for line in input.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            let mut swp = Vec::new();
-            std::mem::swap(&mut swp, &mut current);
-            elves.push(swp);
-        } else {
-            let value = line
-                .parse()
-                .with_context(|| format!("`{}` was not a number", line))?;
-            current.push(value)
-        }
-    }
+pub fn parse_input(input: &str) -> Result<Vec<Vec<i64>>> {
+    each_record(input, |elf| {
+        lines(elf.text, |l| integer(l.trim(), "calories"))
+    })
+}
 
-    if !current.is_empty() {
-        elves.push(current);
-    }
-    Ok(elves)
+/// `size` elves, each carrying a handful of snacks
+pub fn generate(rng: &mut fastrand::Rng, size: usize) -> String {
+    (0..size.max(3))
+        .map(|_| {
+            (0..rng.usize(1..=15))
+                .map(|_| format!("{}\n", rng.u32(1000..=60000)))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::challenge::Solution;
     const INPUT: &str = include_str!("../../../input/day1");
     const EX: &str = include_str!("../../../input/day1_ex");
 
+    #[test]
+    fn crlf_records() {
+        let elves = parse_input("1000\r\n2000\r\n\r\n3000\r\n").unwrap();
+        assert_eq!(elves, vec![vec![1000, 2000], vec![3000]]);
+        let e = parse_input("1000\n\n3000\nlots\n").unwrap_err();
+        assert_eq!(e.to_string(), "record 2 (starting on line 3)");
+    }
+
     #[test]
     fn verify_p1() {
-        assert_eq!(part1(INPUT).unwrap().as_str(), "71502")
+        assert_eq!(Day1.part1(INPUT).unwrap().to_string(), "71502")
     }
     #[test]
     fn verify_p2() {
-        assert_eq!(part2(INPUT).unwrap().as_str(), "208191")
+        assert_eq!(Day1.part2(INPUT).unwrap().to_string(), "208191")
     }
     #[test]
     fn part1_ex() {
-        assert_eq!(part1(EX).unwrap().as_str(), "24000")
+        assert_eq!(Day1.part1(EX).unwrap().to_string(), "24000")
     }
     #[test]
     fn part2_ex() {
-        assert_eq!(part2(EX).unwrap().as_str(), "45000")
+        assert_eq!(Day1.part2(EX).unwrap().to_string(), "45000")
     }
 }
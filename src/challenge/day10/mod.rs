@@ -1,15 +1,91 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
+
+use super::{
+    parse_error::Locate,
+    parser::{integer, lines, template},
+    Answer, Day, Example, Expected, Param, RunContext,
+};
 
 const INTERESTING_SIGNALS: &[usize] = &[20, 60, 100, 140, 180, 220];
 const SCREEN_WIDTH: usize = 40;
 const SCREEN_HEIGHT: usize = 6;
+const MAX_SCREEN_PIXELS: usize = 1 << 20;
+
+const P2_EX_ANS: &str = r###"##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....
+"###;
+
+pub struct Day10;
+
+impl Day for Day10 {
+    type Input = Vec<Instruction>;
 
-pub fn part1(input: &str) -> Result<String> {
-    let program = parse(input)?;
+    fn name(&self) -> &'static str {
+        "Cathode-Ray Tube"
+    }
+    fn day(&self) -> u32 {
+        10
+    }
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+    fn solve_p1(&self, input: &Self::Input, ctx: &RunContext) -> Result<Answer> {
+        let mut signals = ctx.param_list("interesting_signals", INTERESTING_SIGNALS)?;
+        signals.sort_unstable();
+        Ok(solve_p1(input, &signals).into())
+    }
+    fn solve_p2(&self, input: &Self::Input, ctx: &RunContext) -> Result<Answer> {
+        let width = ctx.param("screen_width", SCREEN_WIDTH)?;
+        let height = ctx.param("screen_height", SCREEN_HEIGHT)?;
+        if width == 0 {
+            anyhow::bail!("screen_width must be at least 1")
+        }
+        match width.checked_mul(height) {
+            Some(pixels) if pixels <= MAX_SCREEN_PIXELS => {}
+            _ => anyhow::bail!("screen can have at most {} pixels", MAX_SCREEN_PIXELS),
+        }
+        Ok(solve_p2(input, width, height).into())
+    }
+    fn params(&self) -> &'static [Param] {
+        &[
+            Param {
+                name: "interesting_signals",
+                help: "comma separated cycles summed in part 1 (default 20,60,100,140,180,220)",
+            },
+            Param {
+                name: "screen_width",
+                help: "pixels per CRT row (default 40)",
+            },
+            Param {
+                name: "screen_height",
+                help: "rows on the CRT (default 6)",
+            },
+        ]
+    }
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../../input/day10_ex"),
+            expected: Expected {
+                part1: Some("13140"),
+                part2: Some(P2_EX_ANS),
+            },
+        }]
+    }
+
+    fn generate(&self, rng: &mut fastrand::Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
+}
+
+pub fn solve_p1(program: &[Instruction], signals: &[usize]) -> i64 {
     let cycle_accurate = CycleAccurateInstructions::new(program.iter().cloned());
     log::debug!("{:#?}", program);
     let mut cpu = Cpu::default();
-    let mut check_signals = INTERESTING_SIGNALS.iter();
+    let mut check_signals = signals.iter();
     let mut next_signal = check_signals.next();
     let mut answer = 0;
     for (idx, ins) in cycle_accurate.enumerate() {
@@ -27,19 +103,18 @@ pub fn part1(input: &str) -> Result<String> {
         log::trace!("{}: {:?}", idx, ins);
         cpu.run(&ins)
     }
-    Ok(format!("{:?}", answer))
+    answer
 }
 
-pub fn part2(input: &str) -> Result<String> {
-    let program = parse(input)?;
+pub fn solve_p2(program: &[Instruction], width: usize, height: usize) -> Screen {
     let cycle_accurate = CycleAccurateInstructions::new(program.iter().cloned());
-    let mut screen = Screen::default();
+    let mut screen = Screen::new(width, height);
     let mut cpu = Cpu::default();
-    for (idx, ins) in cycle_accurate.enumerate() {
+    for (idx, ins) in cycle_accurate.take(width * height).enumerate() {
         screen.tick(idx, cpu.register);
         cpu.run(&ins)
     }
-    Ok(format!("{}", screen))
+    screen
 }
 
 struct Cpu {
@@ -61,56 +136,54 @@ impl Cpu {
     }
 }
 
-struct Screen {
+pub struct Screen {
     screen: Vec<bool>,
+    width: usize,
 }
 
 impl Default for Screen {
     fn default() -> Self {
-        Self {
-            screen: vec![false; SCREEN_WIDTH * SCREEN_HEIGHT],
-        }
+        Screen::new(SCREEN_WIDTH, SCREEN_HEIGHT)
     }
 }
 
 impl Screen {
+    fn new(width: usize, height: usize) -> Screen {
+        Screen {
+            screen: vec![false; width * height],
+            width,
+        }
+    }
+
     fn tick(&mut self, cycle: usize, reg: i64) {
-        let row_idx = (cycle % SCREEN_WIDTH) as i64;
+        let row_idx = (cycle % self.width) as i64;
         self.screen[cycle] = (row_idx - reg).abs() <= 1
     }
 }
 
-impl std::fmt::Display for Screen {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for row in 0..SCREEN_HEIGHT {
-            for col in 0..SCREEN_WIDTH {
-                let idx = row * SCREEN_WIDTH + col;
-                write!(f, "{}", if self.screen[idx] { "#" } else { "." })?
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+impl From<Screen> for Answer {
+    fn from(screen: Screen) -> Self {
+        let rows = screen
+            .screen
+            .chunks(screen.width)
+            .map(|row| row.iter().map(|&lit| if lit { '#' } else { '.' }).collect())
+            .collect();
+        Answer::Grid(rows)
     }
 }
 
-fn parse(input: &str) -> Result<Vec<Instruction>> {
-    input
-        .lines()
-        .map(|l| parse_instr(l).with_context(|| format!("could not parse instruction: {:?}", l)))
-        .collect()
+pub fn parse(input: &str) -> Result<Vec<Instruction>> {
+    lines(input, |l| parse_instr(l.trim_end()))
 }
 
 fn parse_instr(input: &str) -> Result<Instruction> {
-    let mut split = input.split_whitespace();
-    let name = aoc::parse::expect_word(&mut split, "instruction name")?;
-    match name {
-        "noop" => Ok(Instruction::NoOp),
-        "addx" => {
-            let val: i64 = aoc::parse::expect_parse(&mut split, "add argument")?;
-            Ok(Instruction::AddX(val))
-        }
-        _ => Err(anyhow::anyhow!("unrecognized instruction {:?}", name)),
+    if input == "noop" {
+        return Ok(Instruction::NoOp);
     }
+    let [val] = template(input, "addx {}")?;
+    Ok(Instruction::AddX(
+        integer(val, "add argument").locate(input, val)?,
+    ))
 }
 
 struct CycleAccurateInstructions<I> {
@@ -158,17 +231,36 @@ where
 }
 
 #[derive(Debug, Clone, Copy)]
-enum Instruction {
+pub enum Instruction {
     NoOp,
     AddX(i64),
 }
 
+/// At least `size` instructions, running long enough to draw the whole screen
+/// and keeping the sprite near it
+pub fn generate(rng: &mut fastrand::Rng, size: usize) -> String {
+    let mut out = String::new();
+    let (mut x, mut cycles, mut count) = (1, 0, 0);
+    while count < size || cycles < SCREEN_WIDTH * SCREEN_HEIGHT {
+        if rng.u8(..3) == 0 {
+            out.push_str("noop\n");
+            cycles += 1;
+        } else {
+            let target = rng.i64(-1..=SCREEN_WIDTH as i64);
+            out.push_str(&format!("addx {}\n", target - x));
+            x = target;
+            cycles += 2;
+        }
+        count += 1;
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::challenge::Solution;
     const INPUT: &str = include_str!("../../../input/day10");
-    const EX: &str = include_str!("../../../input/day10_ex");
-
     const P2_ANS: &str = r###"###..####.#..#.####..##....##..##..###..
 #..#....#.#..#.#....#..#....#.#..#.#..#.
 #..#...#..####.###..#.......#.#....###..
@@ -176,18 +268,31 @@ mod tests {
 #.#..#....#..#.#....#..#.#..#.#..#.#..#.
 #..#.####.#..#.#.....###..##...##..###..
 "###;
+    const EX: &str = include_str!("../../../input/day10_ex");
 
     #[test]
     fn verify_p1() {
-        assert_eq!(part1(INPUT).unwrap().as_str(), "13860")
+        assert_eq!(Day10.part1(INPUT).unwrap().to_string(), "13860")
     }
     #[test]
     fn verify_p2() {
-        assert_eq!(part2(INPUT).unwrap().as_str(), P2_ANS)
+        assert_eq!(Day10.part2(INPUT).unwrap().to_string(), P2_ANS)
     }
 
     #[test]
     fn p1_ex() {
-        assert_eq!(part1(EX).unwrap().as_str(), "13140")
+        assert_eq!(Day10.part1(EX).unwrap().to_string(), "13140")
+    }
+
+    #[test]
+    fn screen_size_override() {
+        let ctx = RunContext::new(true)
+            .with_param("screen_width", "20")
+            .with_param("screen_height", "1");
+        let answer = Day10.solve(2, EX, &ctx).unwrap().to_string();
+        assert_eq!(answer, "##..##..##..##..##..\n");
+
+        let ctx = ctx.with_param("screen_height", "18446744073709551615");
+        assert!(Day10.solve(2, EX, &ctx).is_err());
     }
 }
@@ -1,12 +1,17 @@
 use anyhow::Result;
 
-mod parse;
+use super::{Answer, Day, Example, Expected, Param, RunContext};
+use monkey::{Barrel, Monkey};
+
+pub mod parse;
 
 const ROUNDS_PT1: usize = 20;
 const ROUNDS_PT2: usize = 10_000;
 const MOST_ACTIVE: usize = 2;
+const CALM_PT1: i64 = 3;
+const CALM_PT2: i64 = 1;
 
-mod monkey {
+pub mod monkey {
     #[derive(Debug, Clone, Copy)]
     pub struct Item(pub i64);
 
@@ -26,15 +31,15 @@ mod monkey {
     }
 
     impl Operation {
-        fn op(&self, lhs: i64) -> i64 {
+        fn op(&self, lhs: i64) -> Option<i64> {
             let rhs = match self.arg {
                 Arg::Const(x) => x,
                 Arg::Old => lhs,
             };
 
             match self.code {
-                OpCode::Add => lhs + rhs,
-                OpCode::Mul => lhs * rhs,
+                OpCode::Add => lhs.checked_add(rhs),
+                OpCode::Mul => lhs.checked_mul(rhs),
             }
         }
     }
@@ -61,20 +66,22 @@ mod monkey {
     }
 
     impl Monkey {
-        pub fn handle_item(&self, item: Item, calm: i64) -> (Item, MonkeyId) {
+        /// The item's new worry level and where it is thrown, or `None` if
+        /// the worry level no longer fits in an `i64`
+        pub fn handle_item(&self, item: Item, calm: i64) -> Option<(Item, MonkeyId)> {
             let inital = item.0;
-            let inspected = self.operation.op(inital);
+            let inspected = self.operation.op(inital)?;
             let bored = inspected / calm;
             let target = if bored % self.test == 0 {
                 self.true_monkey
             } else {
                 self.false_monkey
             };
-            (Item(bored), target)
+            Some((Item(bored), target))
         }
     }
 
-    #[derive(Debug, Default)]
+    #[derive(Debug, Default, Clone)]
     pub struct Barrel {
         pub monkeys: Vec<Vec<Item>>,
     }
@@ -96,29 +103,108 @@ mod monkey {
     }
 }
 
-pub fn part1(input: &str) -> Result<String> {
-    let x = monkey_tossing_rounds(input, ROUNDS_PT1, 3)?;
-    Ok(format!("{:?}", x))
+pub struct Day11;
+
+impl Day for Day11 {
+    type Input = (Barrel, Vec<Monkey>);
+
+    fn name(&self) -> &'static str {
+        "Monkey in the Middle"
+    }
+    fn day(&self) -> u32 {
+        11
+    }
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse::parse(input)
+    }
+    fn solve_p1(&self, input: &Self::Input, ctx: &RunContext) -> Result<Answer> {
+        solve(input, ctx, ROUNDS_PT1, CALM_PT1)
+    }
+    fn solve_p2(&self, input: &Self::Input, ctx: &RunContext) -> Result<Answer> {
+        solve(input, ctx, ROUNDS_PT2, CALM_PT2)
+    }
+    fn params(&self) -> &'static [Param] {
+        &[
+            Param {
+                name: "rounds",
+                help: "rounds of keep away (default 20 for part 1, 10000 for part 2)",
+            },
+            Param {
+                name: "most_active",
+                help: "how many of the most active monkeys make up the monkey business (default 2)",
+            },
+            Param {
+                name: "calm",
+                help: "worry level divisor after each inspection (default 3 for part 1, 1 for part 2); \
+                    anything but 1 tracks worry levels in full, so many rounds can overflow",
+            },
+        ]
+    }
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../../input/day11_ex"),
+            expected: Expected {
+                part1: Some("10605"),
+                part2: Some("2713310158"),
+            },
+        }]
+    }
+
+    fn generate(&self, rng: &mut fastrand::Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
 }
 
-pub fn part2(input: &str) -> Result<String> {
-    let x = monkey_tossing_rounds(input, ROUNDS_PT2, 1)?;
-    Ok(format!("{:?}", x))
+fn solve(
+    input: &(Barrel, Vec<Monkey>),
+    ctx: &RunContext,
+    rounds: usize,
+    calm: i64,
+) -> Result<Answer> {
+    let rounds = ctx.param("rounds", rounds)?;
+    let most_active = ctx.param("most_active", MOST_ACTIVE)?;
+    let calm = ctx.param("calm", calm)?;
+    if calm < 1 {
+        anyhow::bail!("calm must be at least 1")
+    }
+    monkey_tossing_rounds(input, rounds, calm, most_active).map(Answer::from)
 }
 
-fn monkey_tossing_rounds(input: &str, rounds: usize, calm_factor: i64) -> Result<i64> {
-    let (mut barrel, monkeys) = parse::parse(input)?;
+/// Worry levels can only be kept modulo the divisors' product when they are
+/// not divided by `calm_factor` as well, so with any other `calm_factor` they
+/// are tracked in full and the rounds fail once they no longer fit in an `i64`.
+pub fn monkey_tossing_rounds(
+    (barrel, monkeys): &(Barrel, Vec<Monkey>),
+    rounds: usize,
+    calm_factor: i64,
+    most_active: usize,
+) -> Result<i64> {
+    let mut barrel = barrel.clone();
     log::debug!("{:#?}", monkeys);
     log::debug!("{:?}", barrel);
 
-    let all_monkey_div = monkeys.iter().map(|m| m.test).product::<i64>() * calm_factor;
+    let all_monkey_div = if calm_factor == 1 {
+        monkeys
+            .iter()
+            .try_fold(1i64, |acc, m| acc.checked_mul(m.test))
+            .ok_or_else(|| anyhow::anyhow!("product of the divisors is too large"))?
+    } else {
+        i64::MAX
+    };
     log::debug!("div: {:?}", all_monkey_div);
     let mut inspections = vec![0; monkeys.len()];
-    for _ in 0..rounds {
-        for monkey in &monkeys {
+    for round in 0..rounds {
+        for monkey in monkeys {
             for item in barrel.pop_monkey(monkey.id) {
                 inspections[monkey.id.0] += 1;
-                let (new_item, target) = monkey.handle_item(item, calm_factor);
+                let (new_item, target) =
+                    monkey.handle_item(item, calm_factor).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "worry level of an item held by monkey {} overflowed in round {}",
+                            monkey.id.0,
+                            round + 1
+                        )
+                    })?;
 
                 barrel.push(target, new_item.op_mod(all_monkey_div));
             }
@@ -126,30 +212,133 @@ fn monkey_tossing_rounds(input: &str, rounds: usize, calm_factor: i64) -> Result
     }
     log::debug!("inspections: {:?}", inspections);
     inspections.sort();
-    Ok(inspections.iter().rev().take(MOST_ACTIVE).product::<i64>())
+    Ok(inspections.iter().rev().take(most_active).product::<i64>())
+}
+
+/// Up to eight monkeys juggling `size` items between them
+pub fn generate(rng: &mut fastrand::Rng, size: usize) -> String {
+    // the worry levels are kept modulo the product of the divisors, which has
+    // to stay small enough to square
+    let mut divisors = vec![2, 3, 5, 7, 11, 13, 17, 19];
+    rng.shuffle(&mut divisors);
+    divisors.truncate(size.clamp(2, 8));
+    let count = divisors.len();
+
+    let mut items = vec![1; count];
+    for _ in count..size {
+        items[rng.usize(..count)] += 1;
+    }
+    let squares = rng.usize(..count);
+
+    divisors
+        .iter()
+        .enumerate()
+        .map(|(id, divisor)| {
+            let operation = if id == squares {
+                "* old".to_string()
+            } else if rng.bool() {
+                format!("* {}", rng.u32(2..=19))
+            } else {
+                format!("+ {}", rng.u32(1..=8))
+            };
+            let if_true = (id + rng.usize(1..count)) % count;
+            let if_false = loop {
+                let target = rng.usize(..count);
+                if target != id && (target != if_true || count == 2) {
+                    break target;
+                }
+            };
+            let starting = (0..items[id])
+                .map(|_| rng.u32(50..100).to_string())
+                .collect::<Vec<_>>();
+            format!(
+                "Monkey {}:\n  Starting items: {}\n  Operation: new = old {}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}\n",
+                id,
+                starting.join(", "),
+                operation,
+                divisor,
+                if_true,
+                if_false
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::challenge::Solution;
     const INPUT: &str = include_str!("../../../input/day11");
     const EX: &str = include_str!("../../../input/day11_ex");
 
     #[test]
     fn verify_p1() {
-        assert_eq!(part1(INPUT).unwrap().as_str(), "98280")
+        assert_eq!(Day11.part1(INPUT).unwrap().to_string(), "98280")
     }
     #[test]
     fn verify_p2() {
-        assert_eq!(part2(INPUT).unwrap().as_str(), "17673687232")
+        assert_eq!(Day11.part2(INPUT).unwrap().to_string(), "17673687232")
     }
 
     #[test]
     fn p1_ex() {
-        assert_eq!(part1(EX).unwrap().as_str(), "10605")
+        assert_eq!(Day11.part1(EX).unwrap().to_string(), "10605")
     }
     #[test]
     fn p2_ex() {
-        assert_eq!(part2(EX).unwrap().as_str(), "2713310158")
+        assert_eq!(Day11.part2(EX).unwrap().to_string(), "2713310158")
+    }
+
+    #[test]
+    fn rounds_override() {
+        // From the puzzle text: after 20 rounds without the relief, the monkeys inspected 99, 97, 8 and 103 items
+        let ctx = RunContext::new(true).with_param("rounds", "20");
+        assert_eq!(Day11.solve(2, EX, &ctx).unwrap().to_string(), "10197");
+        let ctx = ctx.with_param("most_active", "1");
+        assert_eq!(Day11.solve(2, EX, &ctx).unwrap().to_string(), "103");
+    }
+
+    #[test]
+    fn calm_overflow() {
+        let ctx = RunContext::default().with_param("calm", "2");
+        let e = Day11.solve(2, EX, &ctx).unwrap_err();
+        assert!(e.to_string().contains("overflowed"), "{}", e);
+    }
+
+    #[test]
+    fn item_error_location() {
+        let input = EX.replace("54, 65", "54, x5");
+        let e = parse::parse(&input).unwrap_err();
+        let e = e.downcast_ref::<crate::challenge::ParseError>().unwrap();
+        assert_eq!((e.line, e.column), (9, 23));
+        assert_eq!(e.snippet, "  Starting items: 54, x5, 75, 74");
+    }
+
+    #[test]
+    fn failing_monkey_record() {
+        let crlf = EX.replace('\n', "\r\n");
+        assert_eq!(parse::parse(&crlf).unwrap().1.len(), 4);
+
+        let input = EX.replace("  Test: divisible by 19", "  Test: divisible by");
+        let e = parse::parse(&input).unwrap_err();
+        assert_eq!(e.to_string(), "record 2 (starting on line 8)");
+        let e = e.downcast_ref::<crate::challenge::ParseError>().unwrap();
+        assert_eq!(e.line, 11);
+
+        let input = EX.replace("divisible by 19", "divisible by 0");
+        let e = parse::parse(&input).unwrap_err();
+        let e = e.downcast_ref::<crate::challenge::ParseError>().unwrap();
+        assert_eq!(
+            (e.line, e.message.as_str()),
+            (11, "divisor must be at least 1")
+        );
+        let input = EX.replace("throw to monkey 3", "throw to monkey 4");
+        assert!(parse::parse(&input).is_err());
+
+        let missing = EX.replacen("    If false: throw to monkey 3\n", "", 1);
+        let e = parse::parse(&missing).unwrap_err();
+        let e = e.downcast_ref::<crate::challenge::ParseError>().unwrap();
+        assert_eq!(e.line, 5);
     }
 }
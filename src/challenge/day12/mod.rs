@@ -2,55 +2,96 @@ use anyhow::{anyhow, Context, Result};
 use aoc::grid::{compass::Direction, fixed_grid::FixedGrid};
 use petgraph::{data::DataMap, graph::NodeIndex};
 
+use super::{parser::lines, Answer, Day, Example, Expected, ParseError, RunContext};
+
 type TransitGraph = petgraph::graph::DiGraph<i64, ()>;
 
-pub fn part1(input: &str) -> Result<String> {
-    let grid = aoc::grid::fixed_grid::FixedGrid::parse_ascii_grid(input, parse_grid_square)
-        .context("could not parse grid")?;
+pub struct Day12;
+
+impl Day for Day12 {
+    type Input = FixedGrid<GridSquare>;
+
+    fn name(&self) -> &'static str {
+        "Hill Climbing Algorithm"
+    }
+    fn day(&self) -> u32 {
+        12
+    }
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+    fn solve_p1(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        solve_p1(input).map(Answer::from)
+    }
+    fn solve_p2(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        solve_p2(input).map(Answer::from)
+    }
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../../input/day12_ex"),
+            expected: Expected {
+                part1: Some("31"),
+                part2: Some("29"),
+            },
+        }]
+    }
 
+    fn generate(&self, rng: &mut fastrand::Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
+}
+
+pub fn parse(input: &str) -> Result<FixedGrid<GridSquare>> {
+    lines(input, check_squares)?;
+    FixedGrid::parse_ascii_grid(input, parse_grid_square).context("could not parse grid")
+}
+
+pub fn solve_p1(grid: &FixedGrid<GridSquare>) -> Result<i32> {
     log::trace!("\n{}", grid);
 
-    let (start, end, g) = transit_graph(&grid, |src, dst| dst - src <= 1)?;
+    let (start, end, g) = transit_graph(grid, |src, dst| dst - src <= 1)?;
 
     let c = petgraph::algo::dijkstra(&g, start, Some(end), |_| 1);
 
-    let x = c
-        .get(&end)
+    c.get(&end)
         .copied()
-        .ok_or_else(|| anyhow::anyhow!("no path could be found"))?;
-
-    Ok(format!("{:?}", x))
+        .ok_or_else(|| anyhow::anyhow!("no path could be found"))
 }
 
-pub fn part2(input: &str) -> Result<String> {
-    let grid = aoc::grid::fixed_grid::FixedGrid::parse_ascii_grid(input, parse_grid_square)
-        .context("could not parse grid")?;
-
-    let (_, end, g) = transit_graph(&grid, |src, dst| src - dst <= 1)?;
+pub fn solve_p2(grid: &FixedGrid<GridSquare>) -> Result<i32> {
+    let (_, end, g) = transit_graph(grid, |src, dst| src - dst <= 1)?;
 
     let c = petgraph::algo::dijkstra(&g, end, None, |_| 1);
 
-    let x = c
-        .iter()
+    c.iter()
         .filter_map(|(nidx, dist)| g.node_weight(*nidx).map(|h| (*h, dist)))
         .filter(|(h, _)| *h == 0)
         .map(|(_, d)| *d)
         .min()
-        .ok_or_else(|| anyhow::anyhow!("there was no path found"))?;
+        .ok_or_else(|| anyhow::anyhow!("there was no path found"))
+}
 
-    Ok(format!("{:?}", x))
+/// Point at the first square on `line` that isn't a start, end or elevation
+fn check_squares(line: &str) -> Result<()> {
+    for (idx, c) in line.char_indices() {
+        if let Err(e) = parse_grid_square(c) {
+            anyhow::bail!(ParseError::at(line, &line[idx..idx + c.len_utf8()], e))
+        }
+    }
+    Ok(())
 }
 
 fn parse_grid_square(c: char) -> Result<GridSquare> {
     Ok(match c {
         'S' => GridSquare::Start,
         'E' => GridSquare::End,
-        _ => GridSquare::Elevation(c as i64 - 'a' as i64),
+        'a'..='z' => GridSquare::Elevation(c as i64 - 'a' as i64),
+        _ => anyhow::bail!("`{}` is not an elevation", c),
     })
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum GridSquare {
+pub enum GridSquare {
     Start,
     End,
     Elevation(i64),
@@ -124,27 +165,84 @@ where
     Ok((start, end, graph))
 }
 
+/// A heightmap `size` squares wide. A trail that climbs at most one step at a
+/// time leads from `S` to `E`, so there is always a route.
+pub fn generate(rng: &mut fastrand::Rng, size: usize) -> String {
+    let width = size.max(26);
+    let height = (width / 4).max(5);
+    let mut heights = (0..height)
+        .map(|_| (0..width).map(|_| rng.u8(..26)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    // wander up or down a column before moving right, never doubling back
+    let mut trail = Vec::new();
+    let mut y = rng.usize(..height);
+    for x in 0..width {
+        trail.push((x, y));
+        let target = rng.usize(..height);
+        while y != target {
+            y = if target > y { y + 1 } else { y - 1 };
+            trail.push((x, y));
+        }
+    }
+    let mut climbs = vec![false; trail.len() - 1];
+    climbs[..25].iter_mut().for_each(|c| *c = true);
+    rng.shuffle(&mut climbs);
+    let mut h = 0;
+    for (idx, (x, y)) in trail.iter().enumerate() {
+        if idx > 0 && climbs[idx - 1] {
+            h += 1;
+        }
+        heights[*y][*x] = h;
+    }
+
+    let (start, end) = (trail[0], trail[trail.len() - 1]);
+    let mut out = String::new();
+    for (y, row) in heights.iter().enumerate() {
+        for (x, h) in row.iter().enumerate() {
+            out.push(if (x, y) == start {
+                'S'
+            } else if (x, y) == end {
+                'E'
+            } else {
+                (b'a' + h) as char
+            });
+        }
+        out.push('\n');
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::challenge::Solution;
     const INPUT: &str = include_str!("../../../input/day12");
     const EX: &str = include_str!("../../../input/day12_ex");
 
+    #[test]
+    fn garbage_squares() {
+        let e = parse("Sabc\nab1E\n").unwrap_err();
+        let e = e.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((e.line, e.column), (2, 3));
+        assert!(parse("Sab\nabé\n").is_err());
+    }
+
     #[test]
     fn verify_p1() {
-        assert_eq!(part1(INPUT).unwrap().as_str(), "350")
+        assert_eq!(Day12.part1(INPUT).unwrap().to_string(), "350")
     }
     #[test]
     fn verify_p2() {
-        assert_eq!(part2(INPUT).unwrap().as_str(), "349")
+        assert_eq!(Day12.part2(INPUT).unwrap().to_string(), "349")
     }
 
     #[test]
     fn p1_ex() {
-        assert_eq!(part1(EX).unwrap().as_str(), "31")
+        assert_eq!(Day12.part1(EX).unwrap().to_string(), "31")
     }
     #[test]
     fn p2_ex() {
-        assert_eq!(part2(EX).unwrap().as_str(), "29")
+        assert_eq!(Day12.part2(EX).unwrap().to_string(), "29")
     }
 }
@@ -1,8 +1,47 @@
 use anyhow::{Context, Result};
 
-pub fn part1(input: &str) -> Result<String> {
-    let pairs = parse(input)?;
+use super::{
+    parse_error::{Locate, ParseError},
+    parser::each_record,
+    Answer, Day, Example, Expected, RunContext,
+};
 
+pub struct Day13;
+
+impl Day for Day13 {
+    type Input = Vec<Pair>;
+
+    fn name(&self) -> &'static str {
+        "Distress Signal"
+    }
+    fn day(&self) -> u32 {
+        13
+    }
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+    fn solve_p1(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        Ok(solve_p1(input).into())
+    }
+    fn solve_p2(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        Ok(solve_p2(input).into())
+    }
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../../input/day13_ex"),
+            expected: Expected {
+                part1: Some("13"),
+                part2: Some("140"),
+            },
+        }]
+    }
+
+    fn generate(&self, rng: &mut fastrand::Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
+}
+
+pub fn solve_p1(pairs: &[Pair]) -> usize {
     let mut correct_idx_sum = 0;
     for (idx, p) in pairs.iter().enumerate() {
         log::debug!("lhs: {:#?}", p.lhs);
@@ -14,14 +53,13 @@ pub fn part1(input: &str) -> Result<String> {
         }
     }
 
-    Ok(format!("{:?}", correct_idx_sum))
+    correct_idx_sum
 }
 
-pub fn part2(input: &str) -> Result<String> {
-    let pairs = parse(input)?;
+pub fn solve_p2(pairs: &[Pair]) -> usize {
     let mut packets = pairs
-        .into_iter()
-        .flat_map(|p| [p.lhs, p.rhs])
+        .iter()
+        .flat_map(|p| [p.lhs.clone(), p.rhs.clone()])
         .collect::<Vec<_>>();
     let div_2 = Data::List(vec![Data::List(vec![Data::Int(2)])]);
     let div_6 = Data::List(vec![Data::List(vec![Data::Int(6)])]);
@@ -29,36 +67,29 @@ pub fn part2(input: &str) -> Result<String> {
     packets.push(div_6.clone());
     packets.sort();
 
-    let decoder_key = packets
+    packets
         .into_iter()
         .enumerate()
         .filter(|(_, p)| *p == div_2 || *p == div_6)
-        .map(|(idx, _)| idx as i64 + 1)
-        .product::<i64>();
-
-    Ok(format!("{:?}", decoder_key))
+        .map(|(idx, _)| idx + 1)
+        .product::<usize>()
 }
 
-fn parse(input: &str) -> Result<Vec<Pair>> {
-    let mut line_reader = input.lines();
-    let mut get_line = |expect: &str| {
-        line_reader
+pub fn parse(input: &str) -> Result<Vec<Pair>> {
+    each_record(input, |record| {
+        let mut packets = record.lines().map(|l| {
+            let l = l.trim();
+            parse_data(l).locate(record.text, l)
+        });
+        let lhs = packets.next().context("expected lhs packet")??;
+        let rhs = packets
             .next()
-            .map(|s| s.trim())
-            .ok_or_else(|| anyhow::anyhow!("out of lines, expected: {}", expect))
-    };
-    let mut pairs = Vec::new();
-    loop {
-        let lhs_str = get_line("lhs")?;
-        let lhs = parse_data(lhs_str).with_context(|| lhs_str.to_string())?;
-        let rhs_str = get_line("lhs")?;
-        let rhs = parse_data(rhs_str).with_context(|| rhs_str.to_string())?;
-        pairs.push(Pair { lhs, rhs });
-        if get_line("blank").is_err() {
-            break;
+            .ok_or_else(|| ParseError::end_of(record.text, "expected rhs packet"))??;
+        if packets.next().is_some() {
+            anyhow::bail!("expected a blank line after a pair of packets")
         }
-    }
-    Ok(pairs)
+        Ok(Pair { lhs, rhs })
+    })
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -93,7 +124,8 @@ impl<'a> Iterator for Tokenizer<'a> {
     type Item = Token<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let bytes = &self.input.as_bytes()[self.cursor..];
+        let start = self.cursor;
+        let bytes = &self.input.as_bytes()[start..];
         if bytes.is_empty() {
             return None;
         }
@@ -105,12 +137,14 @@ impl<'a> Iterator for Tokenizer<'a> {
 
         for (idx, b) in bytes.iter().enumerate().skip(1) {
             if known_byte(*b).is_some() {
+                // delimiters are ascii, so they always start on a char boundary
                 self.cursor += idx;
-                let s = unsafe { std::str::from_utf8_unchecked(&bytes[0..idx]) };
-                return Some(Token::Value(s));
+                return Some(Token::Value(&self.input[start..self.cursor]));
             }
         }
-        None
+        // a value running to the end of the input
+        self.cursor = self.input.len();
+        Some(Token::Value(&self.input[start..]))
     }
 }
 
@@ -139,18 +173,23 @@ fn parse_data(input: &str) -> Result<Data> {
             }
         }
     }
-    current
-        .pop()
-        .ok_or_else(|| anyhow::anyhow!("input did not have any data"))
+    if !stack.is_empty() {
+        anyhow::bail!("{} `[` never closed", stack.len())
+    }
+    match current.len() {
+        0 => anyhow::bail!("input did not have any data"),
+        1 => Ok(current.pop().unwrap()),
+        n => anyhow::bail!("expected a single packet, found {} values", n),
+    }
 }
 
-struct Pair {
-    lhs: Data,
-    rhs: Data,
+pub struct Pair {
+    pub lhs: Data,
+    pub rhs: Data,
 }
 
 impl Pair {
-    fn check_order(&self) -> bool {
+    pub fn check_order(&self) -> bool {
         self.lhs < self.rhs
     }
 }
@@ -188,7 +227,7 @@ impl Ord for Data {
 }
 
 #[derive(Clone, PartialEq, Eq)]
-enum Data {
+pub enum Data {
     Int(i64),
     List(Vec<Data>),
 }
@@ -212,26 +251,68 @@ impl std::fmt::Debug for Data {
     }
 }
 
+/// `size` pairs of packets, nested a few lists deep
+pub fn generate(rng: &mut fastrand::Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| format!("{}\n{}\n", generate_packet(rng, 0), generate_packet(rng, 0)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn generate_packet(rng: &mut fastrand::Rng, depth: usize) -> String {
+    let values = (0..rng.usize(0..=5))
+        .map(|_| {
+            if depth < 4 && rng.u8(..3) == 0 {
+                generate_packet(rng, depth + 1)
+            } else {
+                rng.u8(0..=10).to_string()
+            }
+        })
+        .collect::<Vec<_>>();
+    format!("[{}]", values.join(","))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::challenge::Solution;
     const INPUT: &str = include_str!("../../../input/day13");
     const EX: &str = include_str!("../../../input/day13_ex");
 
+    #[test]
+    fn pair_records() {
+        assert_eq!(parse(&EX.replace('\n', "\r\n")).unwrap().len(), 8);
+        assert_eq!(parse(&format!("\n{}\n\n", EX)).unwrap().len(), 8);
+
+        let e = parse("[1]\n[2]\n\n[3]\n").err().unwrap();
+        assert_eq!(e.to_string(), "record 2 (starting on line 4)");
+        let e = e.downcast::<ParseError>().unwrap();
+        assert_eq!((e.line, e.message.as_str()), (4, "expected rhs packet"));
+
+        let e = parse("[1]\n[2]\n[3]\n").err().unwrap();
+        assert_eq!(e.to_string(), "record 1 (starting on line 1)");
+
+        assert!(parse_data("[[1]").is_err());
+        assert!(parse_data("[1][2]").is_err());
+        assert!(parse_data("[1]abc").is_err());
+        assert!(parse_data("[1]5").is_err());
+        assert_eq!(format!("{:?}", parse_data("[[1],2]").unwrap()), "[[1],2]");
+    }
+
     #[test]
     fn verify_p1() {
-        assert_eq!(part1(INPUT).unwrap().as_str(), "6076")
+        assert_eq!(Day13.part1(INPUT).unwrap().to_string(), "6076")
     }
     #[test]
     fn verify_p2() {
-        assert_eq!(part2(INPUT).unwrap().as_str(), "24805")
+        assert_eq!(Day13.part2(INPUT).unwrap().to_string(), "24805")
     }
     #[test]
     fn p1_ex() {
-        assert_eq!(part1(EX).unwrap().as_str(), "13")
+        assert_eq!(Day13.part1(EX).unwrap().to_string(), "13")
     }
     #[test]
     fn p2_ex() {
-        assert_eq!(part2(EX).unwrap().as_str(), "140")
+        assert_eq!(Day13.part2(EX).unwrap().to_string(), "140")
     }
 }
@@ -1,26 +1,64 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use aoc::{grid::pointmap_grid::PointMap, Point};
 
-pub fn part1(input: &str) -> Result<String> {
+use super::{
+    parse_error::Locate,
+    parser::{integer, lines, separated, template},
+    Answer, Day, Example, Expected, RunContext,
+};
+
+pub struct Day14;
+
+impl Day for Day14 {
+    type Input = Vec<LineSegment>;
+
+    fn name(&self) -> &'static str {
+        "Regolith Reservoir"
+    }
+    fn day(&self) -> u32 {
+        14
+    }
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+    fn solve_p1(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        Ok(solve_p1(input).into())
+    }
+    fn solve_p2(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        Ok(solve_p2(input).into())
+    }
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../../input/day14_ex"),
+            expected: Expected {
+                part1: Some("24"),
+                part2: Some("93"),
+            },
+        }]
+    }
+
+    fn generate(&self, rng: &mut fastrand::Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
+}
+
+pub fn solve_p1(segments: &[LineSegment]) -> usize {
     let grid = WallGrid {
         has_floor: false,
         ..Default::default()
     };
-    let c = count_falling_grains(grid, input)?;
-    Ok(format!("{:?}", c))
+    count_falling_grains(grid, segments)
 }
 
-pub fn part2(input: &str) -> Result<String> {
+pub fn solve_p2(segments: &[LineSegment]) -> usize {
     let grid = WallGrid {
         has_floor: true,
         ..Default::default()
     };
-    let c = count_falling_grains(grid, input)?;
-    Ok(format!("{:?}", c))
+    count_falling_grains(grid, segments)
 }
 
-fn count_falling_grains(mut grid: WallGrid, input: &str) -> Result<usize> {
-    let segments = parse(input)?;
+fn count_falling_grains(mut grid: WallGrid, segments: &[LineSegment]) -> usize {
     log::debug!("{:#?}", segments);
 
     for p in segments.iter().flat_map(|s| s.trace()) {
@@ -37,30 +75,24 @@ fn count_falling_grains(mut grid: WallGrid, input: &str) -> Result<usize> {
             break;
         }
     }
-    Ok(c)
+    c
 }
 
-fn parse(input: &str) -> Result<Vec<LineSegment>> {
-    input
-        .lines()
-        .map(|l| parse_line_segment(l).with_context(|| format!("could not parse number: {:?}", l)))
-        .collect()
+pub fn parse(input: &str) -> Result<Vec<LineSegment>> {
+    lines(input, parse_line_segment)
 }
 
 fn parse_line_segment(input: &str) -> Result<LineSegment> {
-    let points = input
-        .split("->")
-        .map(parse_point)
-        .collect::<Result<Vec<_>>>()?;
+    let points = separated(input, " -> ", parse_point)?;
     Ok(LineSegment { points })
 }
 
 fn parse_point(input: &str) -> Result<Point> {
-    let input = input.trim();
-    let mut split = input.split(',');
-    let x: i64 = aoc::parse::expect_parse(&mut split, "point x")?;
-    let y: i64 = aoc::parse::expect_parse(&mut split, "point y")?;
-    Ok(Point::new(x, y))
+    let [x, y] = template(input, "{},{}")?;
+    Ok(Point::new(
+        integer(x, "point x").locate(input, x)?,
+        integer(y, "point y").locate(input, y)?,
+    ))
 }
 
 fn sand_fall(grid: &WallGrid, path: &mut Vec<Point>) -> Option<Point> {
@@ -138,8 +170,8 @@ impl std::fmt::Display for WallData {
 }
 
 #[derive(Debug)]
-struct LineSegment {
-    points: Vec<Point>,
+pub struct LineSegment {
+    pub points: Vec<Point>,
 }
 
 impl LineSegment {
@@ -172,27 +204,60 @@ fn unit(x: i64) -> i64 {
     }
 }
 
+/// `size` rock paths below the sand source, each a few straight segments long
+pub fn generate(rng: &mut fastrand::Rng, size: usize) -> String {
+    let depth = 15 + size as i64;
+    let spread = depth / 2;
+    (0..size.max(1))
+        .map(|_| {
+            let mut pt = Point::new(rng.i64(500 - spread..=500 + spread), rng.i64(10..=depth));
+            let mut path = vec![pt];
+            let horizontal = rng.bool();
+            for segment in 0..rng.usize(1..=5) {
+                let step = rng.i64(1..=8) * if rng.bool() { 1 } else { -1 };
+                if (segment % 2 == 0) == horizontal {
+                    pt.x += step;
+                } else {
+                    pt.y = (pt.y + step).clamp(10, depth);
+                }
+                if path.last() != Some(&pt) {
+                    path.push(pt);
+                }
+            }
+            if path.len() == 1 {
+                path.push(pt + Point::new(1, 0));
+            }
+            let points = path
+                .iter()
+                .map(|p| format!("{},{}", p.x, p.y))
+                .collect::<Vec<_>>();
+            format!("{}\n", points.join(" -> "))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::challenge::Solution;
     const INPUT: &str = include_str!("../../../input/day14");
     const EX: &str = include_str!("../../../input/day14_ex");
 
     #[test]
     fn verify_p1() {
-        assert_eq!(part1(INPUT).unwrap().as_str(), "715")
+        assert_eq!(Day14.part1(INPUT).unwrap().to_string(), "715")
     }
     #[test]
     fn verify_p2() {
-        assert_eq!(part2(INPUT).unwrap().as_str(), "25248")
+        assert_eq!(Day14.part2(INPUT).unwrap().to_string(), "25248")
     }
     #[test]
     fn pt1_ex() {
-        assert_eq!(part1(EX).unwrap().as_str(), "24")
+        assert_eq!(Day14.part1(EX).unwrap().to_string(), "24")
     }
 
     #[test]
     fn pt2_ex() {
-        assert_eq!(part2(EX).unwrap().as_str(), "93")
+        assert_eq!(Day14.part2(EX).unwrap().to_string(), "93")
     }
 }
@@ -2,36 +2,100 @@ use std::collections::HashSet;
 
 use anyhow::{anyhow, Context, Result};
 use aoc::Point;
-mod multi_range;
+
+use super::{
+    arg,
+    parse_error::Locate,
+    parser::{integer, lines, template},
+    Answer, Command, Day, Example, Expected, Param, RunContext,
+};
+
+pub mod multi_range;
+
+pub use multi_range::MultiRange;
 
 const PART1_INTERESTED_ROW: i64 = 2_000_000;
 const PART1_EX_ROW: i64 = 10;
 
 const PART2_MAX_COORD: i64 = 4_000_000;
 const PART2_EX_COORD: i64 = 20;
+const TUNING_MULTIPLIER: i64 = 4_000_000;
+
+pub struct Day15;
 
-fn is_example(readings: &[SensorReading]) -> Result<bool> {
-    Ok(readings
-        .iter()
-        .map(|r| r.beacon.y)
-        .max()
-        .ok_or_else(|| anyhow::anyhow!("no sensor readings"))?
-        < 25)
+impl Day for Day15 {
+    type Input = Vec<SensorReading>;
+
+    fn name(&self) -> &'static str {
+        "Beacon Exclusion Zone"
+    }
+    fn day(&self) -> u32 {
+        15
+    }
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+    fn solve_p1(&self, input: &Self::Input, ctx: &RunContext) -> Result<Answer> {
+        let row = if ctx.example {
+            PART1_EX_ROW
+        } else {
+            PART1_INTERESTED_ROW
+        };
+        Ok(solve_p1(input, ctx.param("row", row)?).into())
+    }
+    fn solve_p2(&self, input: &Self::Input, ctx: &RunContext) -> Result<Answer> {
+        let max_coord = if ctx.example {
+            PART2_EX_COORD
+        } else {
+            PART2_MAX_COORD
+        };
+        let max_coord = ctx.param("max_coord", max_coord)?;
+        if max_coord < 0 {
+            anyhow::bail!("max_coord must not be negative, got {}", max_coord)
+        }
+        solve_p2(input, max_coord).map(Answer::from)
+    }
+    fn params(&self) -> &'static [Param] {
+        &[
+            Param {
+                name: "row",
+                help: "row checked for beacon-free positions in part 1 (default 2000000, 10 for examples)",
+            },
+            Param {
+                name: "max_coord",
+                help: "largest coordinate the distress beacon can have (default 4000000, 20 for examples)",
+            },
+        ]
+    }
+    fn commands(&self) -> &'static [Command<Self::Input>] {
+        &[Command {
+            name: "row",
+            args: "<y>",
+            help: "ranges of row <y> covered by the sensors, and how many positions can't hold a beacon",
+            run: row_coverage,
+        }]
+    }
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../../input/day15_ex"),
+            expected: Expected {
+                part1: Some("26"),
+                part2: Some("56000011"),
+            },
+        }]
+    }
+
+    fn generate(&self, rng: &mut fastrand::Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
 }
 
-pub fn part1(input: &str) -> Result<String> {
-    let readings = parse(input)?;
-    let row = if is_example(&readings)? {
-        PART1_EX_ROW
-    } else {
-        PART1_INTERESTED_ROW
-    };
-    let x = check_row_no_beacons(&readings, row);
-    Ok(format!("{:?}", x))
+pub fn solve_p1(readings: &[SensorReading], row: i64) -> usize {
+    check_row_no_beacons(readings, row)
 }
 
-fn check_row_no_beacons(readings: &[SensorReading], row: i64) -> usize {
-    let mut mr = multi_range::MultiRange::default();
+pub fn check_row_no_beacons(readings: &[SensorReading], row: i64) -> usize {
+    let mut mr = MultiRange::default();
     for s in readings {
         log::debug!("{:?} -> {}", s, s.radius());
         if let Some((start, end)) = s.cross_section_at_y(row) {
@@ -49,24 +113,36 @@ fn check_row_no_beacons(readings: &[SensorReading], row: i64) -> usize {
     mr.count() - bseen.len()
 }
 
-pub fn part2(input: &str) -> Result<String> {
-    let readings = parse(input)?;
-    let max_coord = if is_example(&readings)? {
-        PART2_EX_COORD
-    } else {
-        PART2_MAX_COORD
-    };
+fn row_coverage(readings: &Vec<SensorReading>, args: &[&str], _ctx: &RunContext) -> Result<String> {
+    let row = arg::<i64>(args, 0, "y")?;
+    let mut mr = MultiRange::default();
+    for s in readings {
+        if let Some((start, end)) = s.cross_section_at_y(row) {
+            mr.add_range(start, end);
+        }
+    }
+    let mut out = mr
+        .iter_ranges()
+        .map(|(start, end)| format!("{}..={}\n", start, end))
+        .collect::<String>();
+    out.push_str(&format!(
+        "{} positions without a beacon\n",
+        check_row_no_beacons(readings, row)
+    ));
+    Ok(out)
+}
 
-    let p = scan_open_coord(&readings, max_coord);
+pub fn solve_p2(readings: &[SensorReading], max_coord: i64) -> Result<i64> {
+    let p = scan_open_coord(readings, max_coord)
+        .ok_or_else(|| anyhow!("no open position within 0..={}", max_coord))?;
     log::debug!("p: {}", p);
 
-    let x = p.x * PART2_MAX_COORD + p.y;
-    Ok(format!("{:?}", x))
+    Ok(p.x * TUNING_MULTIPLIER + p.y)
 }
 
-fn scan_open_coord(readings: &[SensorReading], max_coord: i64) -> Point {
+pub fn scan_open_coord(readings: &[SensorReading], max_coord: i64) -> Option<Point> {
     for row in 0..=max_coord {
-        let mut mr = multi_range::MultiRange::default();
+        let mut mr = MultiRange::default();
         for s in readings {
             if let Some((start, end)) = s.cross_section_at_y(row) {
                 mr.add_range(start, end);
@@ -75,68 +151,48 @@ fn scan_open_coord(readings: &[SensorReading], max_coord: i64) -> Point {
         for (s, e) in mr.iter_ranges() {
             let ts = s - 1;
             let te = e + 1;
-            if ts >= 0 {
-                return Point::new(ts, row);
-            } else if te <= max_coord {
-                return Point::new(te, row);
+            if (0..=max_coord).contains(&ts) {
+                return Some(Point::new(ts, row));
+            } else if (0..=max_coord).contains(&te) {
+                return Some(Point::new(te, row));
             }
         }
     }
-    panic!("none found");
+    None
 }
 
 fn scan_alt(readings: &[SensorReading], max_coord: i64) -> Option<Point> {
     todo!()
 }
 
-fn parse(input: &str) -> Result<Vec<SensorReading>> {
-    input
-        .lines()
-        .map(|l| parse_sensor(l).with_context(|| format!("could not parse sensor: {:?}", l)))
-        .collect()
+pub fn parse(input: &str) -> Result<Vec<SensorReading>> {
+    lines(input, parse_sensor)
 }
 
 fn parse_sensor(input: &str) -> Result<SensorReading> {
-    let mut split = input.split_whitespace();
-    aoc::parse::expect_str_literal(&mut split, "Sensor")?;
-    aoc::parse::expect_str_literal(&mut split, "at")?;
-    let sensor_x_str = aoc::parse::expect_word(&mut split, "sensor x")?;
-    let sensor_y_str = aoc::parse::expect_word(&mut split, "sensor y")?;
-    aoc::parse::expect_str_literal(&mut split, "closest")?;
-    aoc::parse::expect_str_literal(&mut split, "beacon")?;
-    aoc::parse::expect_str_literal(&mut split, "is")?;
-    aoc::parse::expect_str_literal(&mut split, "at")?;
-    let beacon_x_str = aoc::parse::expect_word(&mut split, "beacon x")?;
-    let beacon_y_str = aoc::parse::expect_word(&mut split, "beacon y")?;
-
+    let [sensor_x, sensor_y, beacon_x, beacon_y] = template(
+        input,
+        "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+    )?;
+    let coord = |s| integer(s, "coordinate").locate(input, s);
     Ok(SensorReading {
-        loc: Point::new(parse_coord(sensor_x_str)?, parse_coord(sensor_y_str)?),
-        beacon: Point::new(parse_coord(beacon_x_str)?, parse_coord(beacon_y_str)?),
+        loc: Point::new(coord(sensor_x)?, coord(sensor_y)?),
+        beacon: Point::new(coord(beacon_x)?, coord(beacon_y)?),
     })
 }
 
-fn parse_coord(input: &str) -> Result<i64> {
-    let input = input.strip_suffix(',').unwrap_or(input);
-    let input = input.strip_suffix(':').unwrap_or(input);
-
-    let mut split = input.split('=');
-    aoc::parse::expect_word(&mut split, "dimm")?;
-
-    aoc::parse::expect_parse(&mut split, "coord")
-}
-
 #[derive(Debug)]
-struct SensorReading {
-    loc: Point,
-    beacon: Point,
+pub struct SensorReading {
+    pub loc: Point,
+    pub beacon: Point,
 }
 
 impl SensorReading {
-    fn radius(&self) -> i64 {
+    pub fn radius(&self) -> i64 {
         let d = self.loc - self.beacon;
         d.x.abs() + d.y.abs()
     }
-    fn cross_section_at_y(&self, y: i64) -> Option<(i64, i64)> {
+    pub fn cross_section_at_y(&self, y: i64) -> Option<(i64, i64)> {
         let vertical_dist = (y - self.loc.y).abs();
         let width = self.radius() - vertical_dist;
         let (l, r) = (self.loc.x - width, self.loc.x + width);
@@ -165,27 +221,106 @@ impl SensorReading {
     // }
 }
 
+/// `size` sensors that leave exactly one position within the part 2 search
+/// area uncovered
+pub fn generate(rng: &mut fastrand::Rng, size: usize) -> String {
+    generate_within(rng, size, PART2_MAX_COORD)
+}
+
+/// Four sensors, one diagonally out from the gap in each direction, have
+/// ranges that reach just short of it and cover the rest of the area between
+/// them. The others are scattered, with ranges short of the gap. Beacons only
+/// sit on the edge of their sensor's range, nothing checks that they are the
+/// closest one to any other sensor.
+fn generate_within(rng: &mut fastrand::Rng, size: usize, max_coord: i64) -> String {
+    let gap = Point::new(rng.i64(0..=max_coord), rng.i64(0..=max_coord));
+    let distance = |a: Point, b: Point| (a.x - b.x).abs() + (a.y - b.y).abs();
+    let mut readings = Vec::new();
+    for (dx, dy) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+        let mut reach = || rng.i64(max_coord + 1..=max_coord * 3 / 2 + 1);
+        let loc = gap + Point::new(dx * reach(), dy * reach());
+        readings.push((loc, distance(loc, gap) - 1));
+    }
+    while readings.len() < size {
+        let loc = Point::new(rng.i64(0..=max_coord), rng.i64(0..=max_coord));
+        if distance(loc, gap) > 1 {
+            readings.push((loc, rng.i64(1..distance(loc, gap))));
+        }
+    }
+    rng.shuffle(&mut readings);
+
+    readings
+        .into_iter()
+        .map(|(loc, radius)| {
+            let along = rng.i64(0..radius);
+            let (dx, dy) = [(1, 1), (1, -1), (-1, 1), (-1, -1)][rng.usize(..4)];
+            let beacon = loc + Point::new(dx * along, dy * (radius - along));
+            format!(
+                "Sensor at x={}, y={}: closest beacon is at x={}, y={}\n",
+                loc.x, loc.y, beacon.x, beacon.y
+            )
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::challenge::Solution;
     const INPUT: &str = include_str!("../../../input/day15");
     const EX: &str = include_str!("../../../input/day15_ex");
 
     #[test]
     fn verify_p1() {
-        assert_eq!(part1(INPUT).unwrap().as_str(), "4737443")
+        assert_eq!(Day15.part1(INPUT).unwrap().to_string(), "4737443")
     }
     #[test]
     fn verify_p2() {
-        assert_eq!(part2(INPUT).unwrap().as_str(), "11482462818989")
+        assert_eq!(Day15.part2(INPUT).unwrap().to_string(), "11482462818989")
     }
 
     #[test]
     fn p1_ex() {
-        assert_eq!(part1(EX).unwrap().as_str(), "26")
+        let ctx = RunContext::new(true);
+        assert_eq!(Day15.solve(1, EX, &ctx).unwrap().to_string(), "26")
     }
     #[test]
     fn p2_ex() {
-        assert_eq!(part2(EX).unwrap().as_str(), "56000011")
+        let ctx = RunContext::new(true);
+        assert_eq!(Day15.solve(2, EX, &ctx).unwrap().to_string(), "56000011")
+    }
+
+    #[test]
+    fn generated_single_gap() {
+        for seed in 0..20 {
+            let mut rng = fastrand::Rng::with_seed(seed);
+            let readings = parse(&generate_within(&mut rng, 10, PART2_EX_COORD)).unwrap();
+            let open = (0..=PART2_EX_COORD)
+                .flat_map(|y| (0..=PART2_EX_COORD).map(move |x| Point::new(x, y)))
+                .filter(|p| {
+                    readings.iter().all(|s| {
+                        let d = s.loc - *p;
+                        d.x.abs() + d.y.abs() > s.radius()
+                    })
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(open.len(), 1, "seed {}", seed);
+            assert_eq!(scan_open_coord(&readings, PART2_EX_COORD), Some(open[0]));
+        }
+    }
+
+    #[test]
+    fn max_coord_without_gap() {
+        let ctx = RunContext::new(true).with_param("max_coord", "-1");
+        assert!(Day15.solve(2, EX, &ctx).is_err());
+        let ctx = RunContext::new(true).with_param("max_coord", "2");
+        let e = Day15.solve(2, EX, &ctx).unwrap_err();
+        assert_eq!(e.to_string(), "no open position within 0..=2");
+    }
+
+    #[test]
+    fn row_override() {
+        let ctx = RunContext::new(true).with_param("row", "11");
+        assert_eq!(Day15.solve(1, EX, &ctx).unwrap().to_string(), "28")
     }
 }
@@ -165,12 +165,61 @@ fn extend_range(src: ClosedRange, r: ClosedRange) -> RangeCombine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::challenge::oracle;
+    use proptest::{collection::vec, strategy::Strategy};
+    use std::collections::HashSet;
+
+    /// Closed ranges, some of them empty, packed closely enough to overlap
+    fn ranges() -> impl Strategy<Value = Vec<(i64, i64)>> {
+        vec((-30..30i64, -2..12i64), 0..12)
+            .prop_map(|rs| rs.into_iter().map(|(s, len)| (s, s + len)).collect())
+    }
+
+    /// The runs of consecutive points, found by testing every point
+    fn naive_ranges(ranges: &[(i64, i64)]) -> Vec<(i64, i64)> {
+        let points = ranges
+            .iter()
+            .flat_map(|(s, e)| *s..=*e)
+            .collect::<HashSet<_>>();
+        let mut runs: Vec<(i64, i64)> = Vec::new();
+        for x in -40..=50 {
+            if !points.contains(&x) {
+                continue;
+            }
+            match runs.last_mut() {
+                Some((_, end)) if *end == x - 1 => *end = x,
+                _ => runs.push((x, x)),
+            }
+        }
+        runs
+    }
 
     fn check_ranges(mr: &MultiRange, expected: &[(i64, i64)]) {
         let actual = mr.iter_ranges().collect::<Vec<_>>();
         assert_eq!(actual.as_slice(), expected);
     }
 
+    #[test]
+    fn matches_point_set() {
+        oracle::check(
+            ranges(),
+            |ranges| {
+                let mut mr = MultiRange::default();
+                for (s, e) in ranges {
+                    mr.add_range(*s, *e);
+                }
+                let contains = (-40..=50).filter(|x| mr.contains(x)).collect::<Vec<_>>();
+                (mr.iter_ranges().collect::<Vec<_>>(), mr.count(), contains)
+            },
+            |ranges| {
+                let runs = naive_ranges(ranges);
+                let count = runs.iter().map(|(s, e)| (e - s + 1) as usize).sum();
+                let contains = runs.iter().flat_map(|(s, e)| *s..=*e).collect();
+                (runs, count, contains)
+            },
+        );
+    }
+
     #[test]
     fn empty() {
         let mr = MultiRange::default();
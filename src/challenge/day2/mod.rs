@@ -1,32 +1,65 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{Context, Result};
 
-pub fn part1(input: &str) -> Result<String> {
-    let guide = parse(input)?;
-    let score = guide
+use super::{Answer, Day, Example, Expected, RunContext};
+
+pub struct Day2;
+
+impl Day for Day2 {
+    type Input = Vec<(Move, Strategy)>;
+
+    fn name(&self) -> &'static str {
+        "Rock Paper Scissors"
+    }
+    fn day(&self) -> u32 {
+        2
+    }
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+    fn solve_p1(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        Ok(solve_p1(input).into())
+    }
+    fn solve_p2(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        Ok(solve_p2(input).into())
+    }
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../../input/day2_ex"),
+            expected: Expected {
+                part1: Some("15"),
+                part2: Some("12"),
+            },
+        }]
+    }
+
+    fn generate(&self, rng: &mut fastrand::Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
+}
+
+pub fn solve_p1(guide: &[(Move, Strategy)]) -> i64 {
+    guide
         .iter()
         .map(|(o, s)| score_round_part1(*o, *s))
-        .sum::<i64>();
-    Ok(format!("{:?}", score))
+        .sum::<i64>()
 }
 
-pub fn part2(input: &str) -> Result<String> {
-    let guide = parse(input)?;
-    let score = guide
+pub fn solve_p2(guide: &[(Move, Strategy)]) -> i64 {
+    guide
         .iter()
         .map(|(o, s)| score_round_part2(*o, *s))
-        .sum::<i64>();
-    Ok(format!("{:?}", score))
+        .sum::<i64>()
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum Strategy {
+pub enum Strategy {
     X,
     Y,
     Z,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum Move {
+pub enum Move {
     Rock,
     Paper,
     Scissors,
@@ -67,7 +100,7 @@ impl Move {
     }
 }
 
-fn parse(input: &str) -> Result<Vec<(Move, Strategy)>> {
+pub fn parse(input: &str) -> Result<Vec<(Move, Strategy)>> {
     input
         .lines()
         .map(|l| parse_line(l).with_context(|| format!("failed to parse line: `{}`", l)))
@@ -131,28 +164,36 @@ fn score_round_part2(opp: Move, strat: Strategy) -> i64 {
     score_round(my_move, opp)
 }
 
+/// `size` rounds of the strategy guide
+pub fn generate(rng: &mut fastrand::Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| format!("{} {}\n", rng.char('A'..='C'), rng.char('X'..='Z')))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::challenge::Solution;
     const INPUT: &str = include_str!("../../../input/day2");
     const EX: &str = include_str!("../../../input/day2_ex");
 
     #[test]
     fn verify_p1() {
-        assert_eq!(part1(INPUT).unwrap().as_str(), "15632")
+        assert_eq!(Day2.part1(INPUT).unwrap().to_string(), "15632")
     }
     #[test]
     fn verify_p2() {
-        assert_eq!(part2(INPUT).unwrap().as_str(), "14416")
+        assert_eq!(Day2.part2(INPUT).unwrap().to_string(), "14416")
     }
 
     #[test]
     fn p1_ex() {
-        assert_eq!(part1(EX).unwrap().as_str(), "15")
+        assert_eq!(Day2.part1(EX).unwrap().to_string(), "15")
     }
 
     #[test]
     fn p2_ex() {
-        assert_eq!(part2(EX).unwrap().as_str(), "12")
+        assert_eq!(Day2.part2(EX).unwrap().to_string(), "12")
     }
 }
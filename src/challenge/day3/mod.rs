@@ -1,21 +1,51 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{Context, Result};
 
-pub fn part1(input: &str) -> Result<String> {
-    let sacks = parse(input)?;
-    let x = sum_of_duplicate_priorities(&sacks)?;
-    Ok(format!("{:?}", x))
+use super::{Answer, Day, Example, Expected, RunContext};
+
+pub struct Day3;
+
+impl Day for Day3 {
+    type Input = Vec<Rucksack>;
+
+    fn name(&self) -> &'static str {
+        "Rucksack Reorganization"
+    }
+    fn day(&self) -> u32 {
+        3
+    }
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+    fn solve_p1(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        sum_of_duplicate_priorities(input).map(Answer::from)
+    }
+    fn solve_p2(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        solve_p2(input).map(Answer::from)
+    }
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../../input/day3_ex"),
+            expected: Expected {
+                part1: Some("157"),
+                part2: Some("70"),
+            },
+        }]
+    }
+
+    fn generate(&self, rng: &mut fastrand::Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
 }
 
-pub fn part2(input: &str) -> Result<String> {
-    let sacks = parse(input)?;
+pub fn solve_p2(sacks: &[Rucksack]) -> Result<i64> {
     let mut total = 0;
     for group in sacks.chunks_exact(3) {
         total += find_common_element(group)?;
     }
-    Ok(format!("{:?}", total))
+    Ok(total)
 }
 
-fn sum_of_duplicate_priorities(sacks: &[Rucksack]) -> Result<i64> {
+pub fn sum_of_duplicate_priorities(sacks: &[Rucksack]) -> Result<i64> {
     let mut total = 0;
     for s in sacks {
         let idx = first_overlap(&s.c1, &s.c2)
@@ -42,7 +72,7 @@ fn find_common_element(sacks: &[Rucksack]) -> Result<i64> {
     Ok(first.0 as i64 + 1)
 }
 
-fn parse(input: &str) -> Result<Vec<Rucksack>> {
+pub fn parse(input: &str) -> Result<Vec<Rucksack>> {
     input
         .lines()
         .map(|l| parse_line(l).with_context(|| format!("could not parse line: {:?}", l)))
@@ -51,6 +81,9 @@ fn parse(input: &str) -> Result<Vec<Rucksack>> {
 
 fn parse_line(input: &str) -> Result<Rucksack> {
     let input = input.trim();
+    if let Some(c) = input.chars().find(|c| !c.is_ascii()) {
+        anyhow::bail!("char `{}` is not a valid item", c)
+    }
     let (c1, c2) = input.split_at(input.len() / 2);
     if c1.len() != c2.len() {
         anyhow::bail!(
@@ -77,7 +110,7 @@ fn parse_compartment(input: &str) -> Result<Compartment> {
 }
 
 #[derive(Debug)]
-struct Rucksack {
+pub struct Rucksack {
     c1: Compartment,
     c2: Compartment,
 }
@@ -117,27 +150,60 @@ fn item_to_idx(c: char) -> Result<usize> {
     }) as usize)
 }
 
+/// `size` groups of three rucksacks. Each rucksack's compartments share exactly
+/// one item type, and each group shares exactly one badge.
+pub fn generate(rng: &mut fastrand::Rng, size: usize) -> String {
+    let items = ('a'..='z').chain('A'..='Z').collect::<Vec<_>>();
+    let mut out = String::new();
+    for _ in 0..size.max(1) {
+        let mut pool = items.clone();
+        rng.shuffle(&mut pool);
+        let badge = pool.pop().unwrap();
+        // every elf in the group packs from its own share of the other items
+        for share in pool.chunks(pool.len() / 3) {
+            let (shared, rest) = share.split_first().unwrap();
+            let (first, second) = rest.split_at(rest.len() / 2);
+            let len = rng.usize(2..=16);
+            let mut c1 = vec![*shared, badge];
+            c1.extend((2..len).map(|_| first[rng.usize(..first.len())]));
+            let mut c2 = vec![*shared];
+            c2.extend((1..len).map(|_| second[rng.usize(..second.len())]));
+            rng.shuffle(&mut c1);
+            rng.shuffle(&mut c2);
+            out.extend(c1.into_iter().chain(c2));
+            out.push('\n');
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::challenge::Solution;
     const INPUT: &str = include_str!("../../../input/day3");
     const EX: &str = include_str!("../../../input/day3_ex");
 
+    #[test]
+    fn non_ascii_items() {
+        assert!(parse("aéb\n").is_err());
+    }
+
     #[test]
     fn verify_p1() {
-        assert_eq!(part1(INPUT).unwrap().as_str(), "7716")
+        assert_eq!(Day3.part1(INPUT).unwrap().to_string(), "7716")
     }
     #[test]
     fn verify_p2() {
-        assert_eq!(part2(INPUT).unwrap().as_str(), "2973")
+        assert_eq!(Day3.part2(INPUT).unwrap().to_string(), "2973")
     }
 
     #[test]
     fn p1_ex() {
-        assert_eq!(part1(EX).unwrap().as_str(), "157")
+        assert_eq!(Day3.part1(EX).unwrap().to_string(), "157")
     }
     #[test]
     fn p2_ex() {
-        assert_eq!(part2(EX).unwrap().as_str(), "70")
+        assert_eq!(Day3.part2(EX).unwrap().to_string(), "70")
     }
 }
@@ -1,95 +1,128 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 
-pub fn part1(input: &str) -> Result<String> {
-    let assignment_pairs = parse(input)?;
+use super::{
+    parse_error::Locate,
+    parser::{integer, lines, template},
+    Answer, Day, Example, Expected, RunContext,
+};
+
+pub struct Day4;
+
+impl Day for Day4 {
+    type Input = Vec<(Assignment, Assignment)>;
+
+    fn name(&self) -> &'static str {
+        "Camp Cleanup"
+    }
+    fn day(&self) -> u32 {
+        4
+    }
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+    fn solve_p1(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        Ok(solve_p1(input).into())
+    }
+    fn solve_p2(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        Ok(solve_p2(input).into())
+    }
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../../input/day4_ex"),
+            expected: Expected {
+                part1: Some("2"),
+                part2: Some("4"),
+            },
+        }]
+    }
+
+    fn generate(&self, rng: &mut fastrand::Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
+}
+
+pub fn solve_p1(assignment_pairs: &[(Assignment, Assignment)]) -> usize {
     log::debug!("{:#?}", assignment_pairs);
-    let fully_contains_count = assignment_pairs
+    assignment_pairs
         .iter()
         .filter(|(a, b)| a.fully_contains(b) || b.fully_contains(a))
-        .count();
-    Ok(format!("{:?}", fully_contains_count))
+        .count()
 }
 
-pub fn part2(input: &str) -> Result<String> {
-    let assignment_pairs = parse(input)?;
+pub fn solve_p2(assignment_pairs: &[(Assignment, Assignment)]) -> usize {
     log::debug!("{:#?}", assignment_pairs);
-    let any_overlap = assignment_pairs
+    assignment_pairs
         .iter()
         .filter(|(a, b)| a.any_overlap(b) || b.any_overlap(a))
-        .count();
-    Ok(format!("{:?}", any_overlap))
+        .count()
 }
 
-fn parse(input: &str) -> Result<Vec<(Assignment, Assignment)>> {
-    input
-        .lines()
-        .map(|l| parse_pair(l).with_context(|| format!("could not parse line: {:?}", l)))
-        .collect()
+pub fn parse(input: &str) -> Result<Vec<(Assignment, Assignment)>> {
+    lines(input, parse_pair)
 }
 
 fn parse_pair(input: &str) -> Result<(Assignment, Assignment)> {
-    let mut pair = input.splitn(2, ',');
-    let first = pair
-        .next()
-        .ok_or_else(|| anyhow::anyhow!("parse first pair"))?;
-    let second = pair
-        .next()
-        .ok_or_else(|| anyhow::anyhow!("parse second pair"))?;
-    Ok((parse_assignment(first)?, parse_assignment(second)?))
-}
-
-fn parse_assignment(input: &str) -> Result<Assignment> {
-    let mut pair = input.splitn(2, '-');
-    let first = pair
-        .next()
-        .ok_or_else(|| anyhow::anyhow!("parse first pair"))?;
-    let second = pair
-        .next()
-        .ok_or_else(|| anyhow::anyhow!("parse second pair"))?;
-    let start = first
-        .parse::<i64>()
-        .with_context(|| format!("could not parse int: {:?}", first))?;
-    let end = second
-        .parse::<i64>()
-        .with_context(|| format!("could not parse int: {:?}", second))?;
-    Ok(Assignment { start, end })
+    let [s1, e1, s2, e2] = template(input, "{}-{},{}-{}")?;
+    let section = |s| integer(s, "section").locate(input, s);
+    Ok((
+        Assignment {
+            start: section(s1)?,
+            end: section(e1)?,
+        },
+        Assignment {
+            start: section(s2)?,
+            end: section(e2)?,
+        },
+    ))
 }
 
 #[derive(Debug)]
-struct Assignment {
-    start: i64,
-    end: i64,
+pub struct Assignment {
+    pub start: i64,
+    pub end: i64,
 }
 
 impl Assignment {
-    fn fully_contains(&self, other: &Assignment) -> bool {
+    pub fn fully_contains(&self, other: &Assignment) -> bool {
         self.start <= other.start && self.end >= other.end
     }
-    fn any_overlap(&self, other: &Assignment) -> bool {
+    pub fn any_overlap(&self, other: &Assignment) -> bool {
         self.start <= other.end && self.end >= other.start
     }
 }
 
+/// `size` pairs of section assignments
+pub fn generate(rng: &mut fastrand::Rng, size: usize) -> String {
+    let mut assignment = || {
+        let start = rng.u32(1..=99);
+        format!("{}-{}", start, rng.u32(start..=99))
+    };
+    (0..size)
+        .map(|_| format!("{},{}\n", assignment(), assignment()))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::challenge::Solution;
     const INPUT: &str = include_str!("../../../input/day4");
     const EX: &str = include_str!("../../../input/day4_ex");
 
     #[test]
     fn verify_p1() {
-        assert_eq!(part1(INPUT).unwrap().as_str(), "567")
+        assert_eq!(Day4.part1(INPUT).unwrap().to_string(), "567")
     }
     #[test]
     fn verify_p2() {
-        assert_eq!(part2(INPUT).unwrap().as_str(), "907")
+        assert_eq!(Day4.part2(INPUT).unwrap().to_string(), "907")
     }
     #[test]
     fn p1_ex() {
-        assert_eq!(part1(EX).unwrap().as_str(), "2")
+        assert_eq!(Day4.part1(EX).unwrap().to_string(), "2")
     }
     #[test]
     fn p2_ex() {
-        assert_eq!(part2(EX).unwrap().as_str(), "4")
+        assert_eq!(Day4.part2(EX).unwrap().to_string(), "4")
     }
 }
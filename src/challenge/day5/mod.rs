@@ -1,71 +1,180 @@
-use anyhow::{Context, Result};
-mod parse;
+use anyhow::Result;
+
+use super::{arg, Answer, Command, Day, Example, Expected, Param, RunContext};
+
+pub mod parse;
 
 const TOWER_WIDTH: usize = 9;
 
-pub fn part1(input: &str) -> Result<String> {
-    run_day5(input, false)
+pub struct Day5;
+
+impl Day for Day5 {
+    type Input = (TowerState, Vec<Instruction>);
+
+    fn name(&self) -> &'static str {
+        "Supply Stacks"
+    }
+    fn day(&self) -> u32 {
+        5
+    }
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse::parse(input)
+    }
+    fn solve_p1(&self, input: &Self::Input, ctx: &RunContext) -> Result<Answer> {
+        run_day5(input, false, ctx.param("tower_width", TOWER_WIDTH)?).map(Answer::from)
+    }
+    fn solve_p2(&self, input: &Self::Input, ctx: &RunContext) -> Result<Answer> {
+        run_day5(input, true, ctx.param("tower_width", TOWER_WIDTH)?).map(Answer::from)
+    }
+    fn params(&self) -> &'static [Param] {
+        &[Param {
+            name: "tower_width",
+            help: "number of stacks in the tower (default 9)",
+        }]
+    }
+    fn commands(&self) -> &'static [Command<Self::Input>] {
+        &[Command {
+            name: "moves",
+            args: "<count> [9001]",
+            help:
+                "show the tower after the first <count> moves, optionally with the CrateMover 9001",
+            run: moves,
+        }]
+    }
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../../input/day5_ex"),
+            expected: Expected {
+                part1: Some("CMZ"),
+                part2: Some("MCD"),
+            },
+        }]
+    }
+
+    fn generate(&self, rng: &mut fastrand::Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
 }
 
-pub fn part2(input: &str) -> Result<String> {
-    run_day5(input, true)
+pub fn run_day5(
+    (tower, instructions): &(TowerState, Vec<Instruction>),
+    part2: bool,
+    width: usize,
+) -> Result<String> {
+    let mut tower = sized_tower(tower, width)?;
+    apply(&mut tower, instructions, part2)?;
+    Ok(tower.top_of_stack())
+}
+
+/// A copy of the parsed tower with `width` stacks
+fn sized_tower(tower: &TowerState, width: usize) -> Result<TowerState> {
+    let mut tower = tower.clone();
+    if tower.tower.len() > width {
+        anyhow::bail!(
+            "input has {} stacks, which is more than the tower width {}",
+            tower.tower.len(),
+            width
+        )
+    }
+    tower.tower.resize(width, Vec::new());
+    Ok(tower)
 }
 
-fn run_day5(input: &str, part2: bool) -> Result<String> {
-    let (mut tower, instructions) = parse::parse(input)?;
+fn apply(tower: &mut TowerState, instructions: &[Instruction], part2: bool) -> Result<()> {
     log::debug!("tower: \n{}", tower);
     for instr in instructions {
+        if instr.src.max(instr.dst) >= tower.tower.len() {
+            anyhow::bail!("instruction outside of the tower: {}", instr)
+        }
         if !part2 {
-            tower.update(&instr);
+            tower.update(instr)?;
         } else {
-            tower.update9001(&instr);
+            tower.update9001(instr)?;
         }
         log::debug!("{}: \n{}", instr, tower);
     }
-    Ok(tower.top_of_stack())
+    Ok(())
+}
+
+fn moves(
+    (tower, instructions): &(TowerState, Vec<Instruction>),
+    args: &[&str],
+    ctx: &RunContext,
+) -> Result<String> {
+    let count = arg::<usize>(args, 0, "count")?;
+    let part2 = match args.get(1) {
+        None => false,
+        Some(&"9001") => true,
+        Some(model) => anyhow::bail!("unknown crane model {:?}", model),
+    };
+    let instructions = instructions
+        .get(..count)
+        .ok_or_else(|| anyhow::anyhow!("there are only {} moves", instructions.len()))?;
+    let mut tower = sized_tower(tower, ctx.param("tower_width", TOWER_WIDTH)?)?;
+    apply(&mut tower, instructions, part2)?;
+    Ok(tower.to_string())
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 struct Block(char);
 
-#[derive(Debug, PartialEq, Default)]
-pub(crate) struct TowerState {
-    tower: [Vec<Block>; TOWER_WIDTH],
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TowerState {
+    tower: Vec<Vec<Block>>,
 }
 
 impl TowerState {
     fn insert(&mut self, col: usize, block: Block) {
+        if self.tower.len() <= col {
+            self.tower.resize(col + 1, Vec::new());
+        }
         self.tower[col].push(block)
     }
 
-    fn get_src_and_dst(&mut self, src: usize, dst: usize) -> (&mut Vec<Block>, &mut Vec<Block>) {
-        match src.cmp(&dst) {
+    /// The stacks moved from and to, checking that `instr` can be carried out
+    fn get_src_and_dst(
+        &mut self,
+        instr: &Instruction,
+    ) -> Result<(&mut Vec<Block>, &mut Vec<Block>)> {
+        let (src, dst) = (instr.src, instr.dst);
+        let (src, dst) = match src.cmp(&dst) {
             std::cmp::Ordering::Less => {
                 let (left, right) = self.tower.split_at_mut(dst);
                 (&mut left[src], &mut right[0])
             }
             std::cmp::Ordering::Equal => {
-                panic!("src and dst can not be the same!")
+                anyhow::bail!("src and dst can not be the same: {}", instr)
             }
             std::cmp::Ordering::Greater => {
                 let (left, right) = self.tower.split_at_mut(src);
                 (&mut right[0], &mut left[dst])
             }
+        };
+        if instr.count > src.len() {
+            anyhow::bail!(
+                "tried to move {} crates from a stack of {}: {}",
+                instr.count,
+                src.len(),
+                instr
+            )
         }
+        Ok((src, dst))
     }
 
-    fn update(&mut self, instr: &Instruction) {
-        let (src, dst) = self.get_src_and_dst(instr.src, instr.dst);
+    pub fn update(&mut self, instr: &Instruction) -> Result<()> {
+        let (src, dst) = self.get_src_and_dst(instr)?;
         for _ in 0..instr.count {
             let block = src.pop().expect("tried to remove from empty stack");
             dst.push(block);
         }
+        Ok(())
     }
-    fn update9001(&mut self, instr: &Instruction) {
-        let (src, dst) = self.get_src_and_dst(instr.src, instr.dst);
+    pub fn update9001(&mut self, instr: &Instruction) -> Result<()> {
+        let (src, dst) = self.get_src_and_dst(instr)?;
         let src_start = src.len() - instr.count;
         dst.extend_from_slice(&src[src_start..]);
         src.truncate(src_start);
+        Ok(())
     }
     fn max_height(&self) -> usize {
         self.tower.iter().map(|s| s.len()).max().unwrap_or(0)
@@ -73,7 +182,7 @@ impl TowerState {
     fn get(&self, col: usize, row: usize) -> Option<Block> {
         self.tower[col].get(row).copied()
     }
-    fn top_of_stack(&self) -> String {
+    pub fn top_of_stack(&self) -> String {
         let mut ans = String::new();
 
         for top in self.tower.iter().filter_map(|v| v.last()) {
@@ -87,7 +196,7 @@ impl std::fmt::Display for TowerState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let max_height = self.max_height();
         for h in (0..max_height).rev() {
-            for c in 0..TOWER_WIDTH {
+            for c in 0..self.tower.len() {
                 if let Some(b) = self.get(c, h) {
                     write!(f, "[{}] ", b.0)?;
                 } else {
@@ -96,7 +205,7 @@ impl std::fmt::Display for TowerState {
             }
             writeln!(f)?;
         }
-        for c in 0..TOWER_WIDTH {
+        for c in 0..self.tower.len() {
             write!(f, " {}  ", c + 1)?;
         }
         writeln!(f)
@@ -116,32 +225,86 @@ impl std::fmt::Display for Instruction {
 }
 
 #[derive(Debug, PartialEq)]
-pub(crate) struct Instruction {
-    src: usize,
-    dst: usize,
-    count: usize,
+pub struct Instruction {
+    pub src: usize,
+    pub dst: usize,
+    pub count: usize,
+}
+
+/// A full tower followed by `size` moves, none of which take more crates than
+/// the stack holds
+pub fn generate(rng: &mut fastrand::Rng, size: usize) -> String {
+    let mut tower = TowerState::default();
+    for col in 0..TOWER_WIDTH {
+        for _ in 0..rng.usize(1..=8) {
+            tower.insert(col, Block(rng.uppercase()));
+        }
+    }
+    let mut out = format!("{}\n", tower);
+    let mut state = tower;
+    for _ in 0..size {
+        let src = loop {
+            let src = rng.usize(..TOWER_WIDTH);
+            if !state.tower[src].is_empty() {
+                break src;
+            }
+        };
+        let dst = (src + rng.usize(1..TOWER_WIDTH)) % TOWER_WIDTH;
+        let instr = Instruction {
+            src,
+            dst,
+            count: rng.usize(1..=state.tower[src].len()),
+        };
+        state
+            .update(&instr)
+            .expect("generated moves only take crates that are there");
+        out.push_str(&format!("{}\n", instr));
+    }
+    out
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::challenge::Solution;
     const INPUT: &str = include_str!("../../../input/day5");
     const EX: &str = include_str!("../../../input/day5_ex");
 
     #[test]
     fn verify_p1() {
-        assert_eq!(part1(INPUT).unwrap().as_str(), "WHTLRMZRC")
+        assert_eq!(Day5.part1(INPUT).unwrap().to_string(), "WHTLRMZRC")
     }
     #[test]
     fn verify_p2() {
-        assert_eq!(part2(INPUT).unwrap().as_str(), "GMPMLWNMG")
+        assert_eq!(Day5.part2(INPUT).unwrap().to_string(), "GMPMLWNMG")
     }
     #[test]
     fn pt1_ex() {
-        assert_eq!(part1(EX).unwrap().as_str(), "CMZ")
+        assert_eq!(Day5.part1(EX).unwrap().to_string(), "CMZ")
     }
     #[test]
     fn pt2_ex() {
-        assert_eq!(part2(EX).unwrap().as_str(), "MCD")
+        assert_eq!(Day5.part2(EX).unwrap().to_string(), "MCD")
+    }
+
+    #[test]
+    fn impossible_moves() {
+        let same = EX.replace("move 1 from 2 to 1", "move 1 from 2 to 2");
+        assert!(Day5.part1(&same).is_err());
+        let over = EX.replace("move 1 from 2 to 1", "move 4 from 2 to 1");
+        assert!(Day5.part1(&over).is_err());
+        assert!(Day5.part2(&over).is_err());
+        let input = parse::parse(&over).unwrap();
+        assert!(moves(&input, &["1", "9001"], &RunContext::default()).is_err());
+    }
+
+    #[test]
+    fn moves_use_tower_width() {
+        let input = parse::parse(EX).unwrap();
+        let ctx = RunContext::default().with_param("tower_width", "4");
+        let tower = moves(&input, &["0"], &ctx).unwrap();
+        assert!(tower.ends_with(" 1   2   3   4  \n"), "{}", tower);
+        let ctx = ctx.with_param("tower_width", "2");
+        assert!(moves(&input, &["0"], &ctx).is_err());
     }
 }
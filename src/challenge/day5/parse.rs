@@ -1,21 +1,19 @@
 use anyhow::Context;
-use aoc::parse;
 
 use super::Block;
 use super::Instruction;
 use super::TowerState;
+use crate::challenge::parse_error::{Locate, ParseError};
+use crate::challenge::parser::{integer, template};
 
-pub(crate) fn parse(input: &str) -> anyhow::Result<(TowerState, Vec<Instruction>)> {
+pub fn parse(input: &str) -> anyhow::Result<(TowerState, Vec<Instruction>)> {
     let mut tower_stack = Vec::new();
     let mut parse_instr = false;
     let mut instructions = Vec::new();
     for l in input.lines() {
         if parse_instr {
             log::trace!("parse instr: {:?}", l);
-            instructions.push(
-                parse_instruction(l)
-                    .with_context(|| format!("could not parse instruction: {:?}", l))?,
-            );
+            instructions.push(parse_instruction(l).locate(input, l)?);
         } else if l.is_empty() {
             parse_instr = true;
             log::trace!("switch to instr parsing");
@@ -51,23 +49,24 @@ fn read_tower_layer(input: &str) -> impl Iterator<Item = (usize, char)> + '_ {
 }
 
 fn parse_instruction(input: &str) -> anyhow::Result<Instruction> {
-    let mut words = input.split_whitespace();
-    parse::expect_str_literal(&mut words, "move")?;
-    let count: usize = parse::expect_parse(&mut words, "count")?;
-    parse::expect_str_literal(&mut words, "from")?;
-    let src: usize = parse::expect_parse(&mut words, "src")?;
-    parse::expect_str_literal(&mut words, "to")?;
-    let dst: usize = parse::expect_parse(&mut words, "dst")?;
+    let [count, src, dst] = template(input, "move {} from {} to {}")?;
+    let stack = |s, name| -> anyhow::Result<usize> {
+        let stack: usize = integer(s, name).locate(input, s)?;
+        stack
+            .checked_sub(1)
+            .ok_or_else(|| ParseError::at(input, s, "stacks are numbered from 1").into())
+    };
     Ok(Instruction {
-        src: src - 1,
-        dst: dst - 1,
-        count,
+        src: stack(src, "src")?,
+        dst: stack(dst, "dst")?,
+        count: integer(count, "count").locate(input, count)?,
     })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::challenge::ParseError;
 
     #[test]
     fn parse_instr_line() {
@@ -89,10 +88,24 @@ mod tests {
         let input = "move 1 from a to 2";
         let instr = parse_instruction(input).unwrap();
     }
+    #[test]
+    fn parse_instr_stack_zero() {
+        let e = parse_instruction("move 1 from 0 to 2").unwrap_err();
+        assert_eq!(e.downcast_ref::<ParseError>().unwrap().column, 13);
+    }
     #[should_panic]
     #[test]
     fn parse_instr_fail_word() {
         let input = "move 1 from 3 two 2";
         let instr = parse_instruction(input).unwrap();
     }
+
+    #[test]
+    fn instruction_error_location() {
+        let input = include_str!("../../../input/day5_ex").replace("move 3 from", "move 3 form");
+        let e = parse(&input).unwrap_err();
+        let e = e.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((e.line, e.column), (7, 8));
+        assert_eq!(e.snippet, "move 3 form 1 to 3");
+    }
 }
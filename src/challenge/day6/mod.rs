@@ -1,24 +1,116 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 
-pub fn part1(input: &str) -> Result<String> {
-    let x = seek_after_marker_pt1(input);
-    Ok(format!("{:?}", x))
+use super::{Answer, Day, Example, Expected, Param, RunContext};
+
+const PACKET_WINDOW: usize = 4;
+const MESSAGE_WINDOW: usize = 14;
+/// A marker can't be longer than the alphabet and still be all distinct
+const MAX_WINDOW: usize = 26;
+
+pub struct Day6;
+
+impl Day for Day6 {
+    type Input = String;
+
+    fn name(&self) -> &'static str {
+        "Tuning Trouble"
+    }
+    fn day(&self) -> u32 {
+        6
+    }
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+    fn solve_p1(&self, input: &Self::Input, ctx: &RunContext) -> Result<Answer> {
+        let window = window(ctx, "packet_window", PACKET_WINDOW)?;
+        Ok(seek_after_marker_n(input, window).into())
+    }
+    fn solve_p2(&self, input: &Self::Input, ctx: &RunContext) -> Result<Answer> {
+        let window = window(ctx, "message_window", MESSAGE_WINDOW)?;
+        Ok(seek_after_marker_n(input, window).into())
+    }
+    fn params(&self) -> &'static [Param] {
+        &[
+            Param {
+                name: "packet_window",
+                help: "distinct characters in a start-of-packet marker (default 4, at most 26)",
+            },
+            Param {
+                name: "message_window",
+                help: "distinct characters in a start-of-message marker (default 14, at most 26)",
+            },
+        ]
+    }
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                input: include_str!("../../../input/day6_ex"),
+                expected: Expected {
+                    part1: Some("7"),
+                    part2: Some("19"),
+                },
+            },
+            Example {
+                input: "bvwbjplbgvbhsrlpgdmjqwftvncz",
+                expected: Expected {
+                    part1: Some("5"),
+                    part2: Some("23"),
+                },
+            },
+            Example {
+                input: "nppdvjthqldpwncqszvftbrmjlhg",
+                expected: Expected {
+                    part1: Some("6"),
+                    part2: Some("23"),
+                },
+            },
+            Example {
+                input: "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg",
+                expected: Expected {
+                    part1: Some("10"),
+                    part2: Some("29"),
+                },
+            },
+            Example {
+                input: "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw",
+                expected: Expected {
+                    part1: Some("11"),
+                    part2: Some("26"),
+                },
+            },
+        ]
+    }
+
+    fn generate(&self, rng: &mut fastrand::Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
 }
 
-pub fn part2(input: &str) -> Result<String> {
-    let x = seek_after_marker_pt2(input);
-    Ok(format!("{:?}", x))
+pub fn parse(input: &str) -> Result<String> {
+    let input = input.trim_end();
+    if let Some(c) = input.chars().find(|c| !c.is_ascii_lowercase()) {
+        anyhow::bail!("signal contains unexpected character: {:?}", c)
+    }
+    Ok(input.to_string())
 }
 
-fn seek_after_marker_pt1(input: &str) -> usize {
-    seek_after_marker_n(input, 4)
+fn window(ctx: &RunContext, key: &str, default: usize) -> Result<usize> {
+    let window = ctx.param(key, default)?;
+    if !(1..=MAX_WINDOW).contains(&window) {
+        anyhow::bail!("{} must be between 1 and {}", key, MAX_WINDOW)
+    }
+    Ok(window)
+}
+
+pub fn seek_after_marker_pt1(input: &str) -> usize {
+    seek_after_marker_n(input, PACKET_WINDOW)
 }
 
-fn seek_after_marker_pt2(input: &str) -> usize {
-    seek_after_marker_n(input, 14)
+pub fn seek_after_marker_pt2(input: &str) -> usize {
+    seek_after_marker_n(input, MESSAGE_WINDOW)
 }
 
-fn seek_after_marker_n(input: &str, window: usize) -> usize {
+pub fn seek_after_marker_n(input: &str, window: usize) -> usize {
     let mut dd = DuplicateDetector::new(window);
 
     for (idx, c) in input.chars().enumerate() {
@@ -64,7 +156,7 @@ impl std::fmt::Display for DuplicateDetector {
 }
 
 #[derive(Debug)]
-struct DuplicateDetector {
+pub struct DuplicateDetector {
     window: usize,
     cursor: usize,
     ring: Vec<usize>,
@@ -72,7 +164,7 @@ struct DuplicateDetector {
 }
 
 impl DuplicateDetector {
-    fn new(window: usize) -> DuplicateDetector {
+    pub fn new(window: usize) -> DuplicateDetector {
         DuplicateDetector {
             window,
             cursor: 0,
@@ -85,7 +177,9 @@ impl DuplicateDetector {
         self.index.iter().any(|c| *c > 1)
     }
 
-    fn feed(&mut self, c: char) -> bool {
+    /// Push the next character, returning `true` if the `window` characters
+    /// before it were all distinct
+    pub fn feed(&mut self, c: char) -> bool {
         let mut duplicates = true;
         let idx = c as usize - 'a' as usize;
         let ring_idx = self.cursor % self.window;
@@ -105,9 +199,24 @@ impl DuplicateDetector {
     }
 }
 
+/// A signal of `size` characters. The start-of-message marker is planted in
+/// noise drawn from too few letters to contain one by chance.
+pub fn generate(rng: &mut fastrand::Rng, size: usize) -> String {
+    let mut signal = (0..size.max(MESSAGE_WINDOW))
+        .map(|_| rng.char('a'..='m'))
+        .collect::<Vec<_>>();
+    let mut marker = ('a'..='z').collect::<Vec<_>>();
+    rng.shuffle(&mut marker);
+    let start = rng.usize((signal.len() - MESSAGE_WINDOW) / 2..=signal.len() - MESSAGE_WINDOW);
+    signal[start..start + MESSAGE_WINDOW].copy_from_slice(&marker[..MESSAGE_WINDOW]);
+    signal.into_iter().chain(Some('\n')).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::challenge::{oracle, Solution};
+    use std::collections::HashSet;
     const INPUT: &str = include_str!("../../../input/day6");
     const EX: &str = include_str!("../../../input/day6_ex");
 
@@ -116,18 +225,29 @@ mod tests {
     const EX3: &str = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
     const EX4: &str = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
 
+    /// Index after the first `window` distinct characters, checking every window
+    fn naive_marker(signal: &str, window: usize) -> usize {
+        let chars = signal.chars().collect::<Vec<_>>();
+        (window..chars.len())
+            .find(|&end| {
+                let seen = chars[end - window..end].iter().collect::<HashSet<_>>();
+                seen.len() == window
+            })
+            .unwrap_or(chars.len())
+    }
+
     #[test]
     fn verify_p1() {
-        assert_eq!(part1(INPUT).unwrap().as_str(), "1262")
+        assert_eq!(Day6.part1(INPUT).unwrap().to_string(), "1262")
     }
     #[test]
     fn verify_p2() {
-        assert_eq!(part2(INPUT).unwrap().as_str(), "3444")
+        assert_eq!(Day6.part2(INPUT).unwrap().to_string(), "3444")
     }
 
     #[test]
     fn pt1_ex() {
-        assert_eq!(part1(EX).unwrap().as_str(), "7")
+        assert_eq!(Day6.part1(EX).unwrap().to_string(), "7")
     }
 
     #[test]
@@ -171,4 +291,23 @@ mod tests {
     fn pt2_ex4() {
         assert_eq!(seek_after_marker_pt2(EX4), 26)
     }
+
+    #[test]
+    fn matches_window_set() {
+        oracle::check(
+            (1..8usize, "[a-h]{0,40}"),
+            |(window, signal)| seek_after_marker_n(signal, *window),
+            |(window, signal)| naive_marker(signal, *window),
+        );
+    }
+
+    #[test]
+    fn window_override() {
+        let ctx = RunContext::new(true).with_param("packet_window", "14");
+        assert_eq!(Day6.solve(1, EX, &ctx).unwrap().to_string(), "19");
+        let ctx = ctx.with_param("packet_window", "0");
+        assert!(Day6.solve(1, EX, &ctx).is_err());
+        let ctx = ctx.with_param("packet_window", "18446744073709551615");
+        assert!(Day6.solve(1, EX, &ctx).is_err());
+    }
 }
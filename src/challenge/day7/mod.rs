@@ -1,25 +1,98 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
+
+use super::{
+    arg,
+    parse_error::Locate,
+    parser::{integer, template},
+    Answer, Command, Day, Example, Expected, Param, RunContext,
+};
 
 const P1_MAX: usize = 100_000;
 
 const DISK_SIZE: usize = 70_000_000;
 const DISK_NEED: usize = 30_000_000;
 
-pub fn part1(input: &str) -> Result<String> {
-    let fs = parse(input)?;
-    let (_, output) = calculate_dir_totals(&fs)?;
-    let p1_sum = output
+pub struct Day7;
+
+impl Day for Day7 {
+    type Input = FileSystem;
+
+    fn name(&self) -> &'static str {
+        "No Space Left On Device"
+    }
+    fn day(&self) -> u32 {
+        7
+    }
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+    fn solve_p1(&self, input: &Self::Input, ctx: &RunContext) -> Result<Answer> {
+        solve_p1(input, ctx.param("p1_max", P1_MAX)?).map(Answer::from)
+    }
+    fn solve_p2(&self, input: &Self::Input, ctx: &RunContext) -> Result<Answer> {
+        let disk_size = ctx.param("disk_size", DISK_SIZE)?;
+        let disk_need = ctx.param("disk_need", DISK_NEED)?;
+        solve_p2(input, disk_size, disk_need).map(Answer::from)
+    }
+    fn params(&self) -> &'static [Param] {
+        &[
+            Param {
+                name: "p1_max",
+                help: "largest directory size counted in part 1 (default 100000)",
+            },
+            Param {
+                name: "disk_size",
+                help: "total size of the disk (default 70000000)",
+            },
+            Param {
+                name: "disk_need",
+                help: "free space needed for the update (default 30000000)",
+            },
+        ]
+    }
+    fn commands(&self) -> &'static [Command<Self::Input>] {
+        &[
+            Command {
+                name: "ls",
+                args: "[path]",
+                help: "list a directory, with the total size of each subdirectory",
+                run: ls,
+            },
+            Command {
+                name: "du",
+                args: "[max]",
+                help: "total size of every directory, largest first, optionally only those up to <max>",
+                run: du,
+            },
+        ]
+    }
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../../input/day7_ex"),
+            expected: Expected {
+                part1: Some("95437"),
+                part2: Some("24933642"),
+            },
+        }]
+    }
+
+    fn generate(&self, rng: &mut fastrand::Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
+}
+
+pub fn solve_p1(fs: &FileSystem, max: usize) -> Result<usize> {
+    let (_, output) = calculate_dir_totals(fs)?;
+    Ok(output
         .iter()
         .map(|(_, t)| *t)
-        .filter(|t| *t <= P1_MAX)
-        .sum::<usize>();
-    Ok(format!("{:?}", p1_sum))
+        .filter(|t| *t <= max)
+        .sum::<usize>())
 }
 
-pub fn part2(input: &str) -> Result<String> {
-    let fs = parse(input)?;
-    let (root_size, output) = calculate_dir_totals(&fs)?;
-    let min_free = (root_size + DISK_NEED) - DISK_SIZE;
+pub fn solve_p2(fs: &FileSystem, disk_size: usize, disk_need: usize) -> Result<usize> {
+    let (root_size, output) = calculate_dir_totals(fs)?;
+    let min_free = (root_size + disk_need).saturating_sub(disk_size);
     let mut min_delete = None;
 
     for (_, total) in output {
@@ -37,20 +110,50 @@ pub fn part2(input: &str) -> Result<String> {
 
     let (_, total) =
         min_delete.ok_or_else(|| anyhow::anyhow!("no directory will free up enough space"))?;
-    Ok(format!("{:?}", total))
+    Ok(total)
 }
 
-fn calculate_dir_totals<'a>(fs: &'a FileSystem) -> Result<(usize, Vec<(&'a Inode<'a>, usize)>)> {
+/// Total size of the root directory, and of every directory below it
+pub fn calculate_dir_totals(fs: &FileSystem) -> Result<(usize, Vec<(&Inode, usize)>)> {
     let mut output = Vec::new();
     let cwd = InodeID(0);
     let stats = dir_total_recurse(fs, cwd, &mut output)?;
     Ok((stats, output))
 }
 
+fn ls(fs: &FileSystem, args: &[&str], _ctx: &RunContext) -> Result<String> {
+    let dir = fs.lookup(args.first().unwrap_or(&"/"))?;
+    let mut out = String::new();
+    for id in fs.get(dir)?.listing().unwrap_or_default() {
+        let inode = fs.get(*id)?;
+        if inode.is_dir() {
+            let total = dir_total_recurse(fs, *id, &mut Vec::new())?;
+            out.push_str(&format!("{:>10}  {}/\n", total, inode.name));
+        } else {
+            out.push_str(&format!("{:>10}  {}\n", inode.size, inode.name));
+        }
+    }
+    Ok(out)
+}
+
+fn du(fs: &FileSystem, args: &[&str], _ctx: &RunContext) -> Result<String> {
+    let max = match args.first() {
+        Some(_) => arg::<usize>(args, 0, "max")?,
+        None => usize::MAX,
+    };
+    let (_, mut dirs) = calculate_dir_totals(fs)?;
+    dirs.retain(|(_, total)| *total <= max);
+    dirs.sort_by_key(|(_, total)| std::cmp::Reverse(*total));
+    Ok(dirs
+        .iter()
+        .map(|(inode, total)| format!("{:>10}  {}\n", total, inode.name))
+        .collect())
+}
+
 fn dir_total_recurse<'a>(
     fs: &'a FileSystem,
     cwd: InodeID,
-    output: &mut Vec<(&'a Inode<'a>, usize)>,
+    output: &mut Vec<(&'a Inode, usize)>,
 ) -> Result<usize> {
     let inode = fs.get(cwd)?;
     let mut total = 0;
@@ -69,7 +172,7 @@ fn dir_total_recurse<'a>(
     Ok(total)
 }
 
-fn parse(input: &str) -> Result<FileSystem> {
+pub fn parse(input: &str) -> Result<FileSystem> {
     let mut fs = FileSystem::default();
     let mut stack = DirStack::new(fs.insert_dir("/"));
     for l in input.lines() {
@@ -77,7 +180,7 @@ fn parse(input: &str) -> Result<FileSystem> {
         if l == "$ ls" {
             continue;
         } else if l.starts_with("$ cd ") {
-            let dir = parse_cd(l)?;
+            let dir = parse_cd(l).locate(input, l)?;
             if dir == ".." {
                 stack.pop();
             } else if dir == "/" {
@@ -90,12 +193,12 @@ fn parse(input: &str) -> Result<FileSystem> {
             }
             log::trace!("cwd => {}", stack);
         } else if l.starts_with("dir ") {
-            let dir = parse_dir_listing(l)?;
+            let dir = parse_dir_listing(l).locate(input, l)?;
             let child = fs.insert_dir(dir);
             fs.add_to_dir(stack.cwd(), child);
             log::trace!("dir {}/{}", stack, dir);
         } else {
-            let (sz, filename) = parse_file_listing(l)?;
+            let (sz, filename) = parse_file_listing(l).locate(input, l)?;
             let child = fs.insert_file(filename, sz);
             fs.add_to_dir(stack.cwd(), child);
             log::trace!("file {}/{} [{} bytes]", stack, filename, sz);
@@ -153,19 +256,28 @@ impl<'a> DirStack<'a> {
 struct InodeID(usize);
 
 #[derive(Debug, Clone)]
-struct Inode<'a> {
-    name: &'a str,
+pub struct Inode {
+    name: String,
     listing: Option<Directory>,
     size: usize,
 }
 
-impl<'a> Inode<'a> {
+impl Inode {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn size(&self) -> usize {
+        self.size
+    }
+    pub fn is_dir(&self) -> bool {
+        self.listing.is_some()
+    }
     fn listing(&self) -> Option<&[InodeID]> {
         self.listing.as_ref().map(|l| l.listing.as_slice())
     }
 }
 
-impl<'a> std::fmt::Display for Inode<'a> {
+impl std::fmt::Display for Inode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.name)
     }
@@ -177,27 +289,27 @@ struct Directory {
 }
 
 #[derive(Debug, Default, Clone)]
-struct FileSystem<'a> {
-    inodes: Vec<Inode<'a>>,
+pub struct FileSystem {
+    inodes: Vec<Inode>,
 }
 
-impl<'a> FileSystem<'a> {
-    fn get(&self, id: InodeID) -> Result<&Inode<'a>> {
+impl FileSystem {
+    fn get(&self, id: InodeID) -> Result<&Inode> {
         self.inodes
             .get(id.0)
             .ok_or_else(|| anyhow::anyhow!("missing inode: {:?}", id))
     }
-    fn insert_dir(&mut self, name: &'a str) -> InodeID {
+    fn insert_dir(&mut self, name: &str) -> InodeID {
         self.inodes.push(Inode {
-            name,
+            name: name.to_string(),
             listing: Some(Directory::default()),
             size: 0,
         });
         InodeID(self.inodes.len() - 1)
     }
-    fn insert_file(&mut self, name: &'a str, size: usize) -> InodeID {
+    fn insert_file(&mut self, name: &str, size: usize) -> InodeID {
         self.inodes.push(Inode {
-            name,
+            name: name.to_string(),
             listing: None,
             size,
         });
@@ -208,6 +320,16 @@ impl<'a> FileSystem<'a> {
             dir.listing.push(child)
         }
     }
+    /// The directory at a `/` separated path from the root
+    fn lookup(&self, path: &str) -> Result<InodeID> {
+        path.split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(InodeID(0), |dir, name| {
+                self.get_subdir(dir, name)
+                    .filter(|id| self.inodes[id.0].is_dir())
+                    .ok_or_else(|| anyhow::anyhow!("no such directory: {:?}", path))
+            })
+    }
     fn get_subdir(&self, node: InodeID, name: &str) -> Option<InodeID> {
         self.inodes
             .get(node.0)
@@ -220,45 +342,106 @@ impl<'a> FileSystem<'a> {
 }
 
 fn parse_cd(input: &str) -> Result<&str> {
-    let mut split = input.split_whitespace();
-    aoc::parse::expect_str_literal(&mut split, "$")?;
-    aoc::parse::expect_str_literal(&mut split, "cd")?;
-    aoc::parse::expect_word(&mut split, "directory")
+    let [dir] = template(input, "$ cd {}")?;
+    Ok(dir)
 }
 
 fn parse_dir_listing(input: &str) -> Result<&str> {
-    let mut split = input.split_whitespace();
-    aoc::parse::expect_str_literal(&mut split, "dir")?;
-    aoc::parse::expect_word(&mut split, "directory")
+    let [dir] = template(input, "dir {}")?;
+    Ok(dir)
 }
 
 fn parse_file_listing(input: &str) -> Result<(usize, &str)> {
-    let mut split = input.split_whitespace();
-    let sz: usize = aoc::parse::expect_parse(&mut split, "file size")?;
-    let filename = aoc::parse::expect_word(&mut split, "file name")?;
-    Ok((sz, filename))
+    let [sz, filename] = template(input, "{} {}")?;
+    Ok((integer(sz, "file size").locate(input, sz)?, filename))
+}
+
+/// A shell session exploring `size` directories, each holding a few files
+pub fn generate(rng: &mut fastrand::Rng, size: usize) -> String {
+    // each directory's parent is one created before it
+    let mut children = vec![Vec::new(); size.max(1)];
+    for dir in 1..children.len() {
+        let parent = rng.usize(..dir);
+        children[parent].push(dir);
+    }
+    let mut out = String::from("$ cd /\n");
+    generate_dir(rng, &children, 0, &mut out);
+    out
+}
+
+fn generate_dir(rng: &mut fastrand::Rng, children: &[Vec<usize>], dir: usize, out: &mut String) {
+    let mut names = std::collections::HashSet::new();
+    let mut name = |rng: &mut fastrand::Rng| loop {
+        let mut name = (0..rng.usize(1..=8))
+            .map(|_| rng.lowercase())
+            .collect::<String>();
+        if rng.bool() {
+            name.push('.');
+            name.extend((0..3).map(|_| rng.lowercase()));
+        }
+        if names.insert(name.clone()) {
+            break name;
+        }
+    };
+
+    let subdirs = children[dir]
+        .iter()
+        .map(|child| (*child, name(rng)))
+        .collect::<Vec<_>>();
+    let mut listing = subdirs
+        .iter()
+        .map(|(_, n)| format!("dir {}\n", n))
+        .collect::<Vec<_>>();
+    for _ in 0..rng.usize(0..=4) {
+        listing.push(format!("{} {}\n", rng.u32(1000..=300000), name(rng)));
+    }
+    rng.shuffle(&mut listing);
+
+    out.push_str("$ ls\n");
+    out.extend(listing);
+    for (child, name) in subdirs {
+        out.push_str(&format!("$ cd {}\n", name));
+        generate_dir(rng, children, child, out);
+        out.push_str("$ cd ..\n");
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::challenge::Solution;
     const INPUT: &str = include_str!("../../../input/day7");
     const EX: &str = include_str!("../../../input/day7_ex");
 
     #[test]
     fn verify_p1() {
-        assert_eq!(part1(INPUT).unwrap().as_str(), "1443806")
+        assert_eq!(Day7.part1(INPUT).unwrap().to_string(), "1443806")
     }
     #[test]
     fn verify_p2() {
-        assert_eq!(part2(INPUT).unwrap().as_str(), "942298")
+        assert_eq!(Day7.part2(INPUT).unwrap().to_string(), "942298")
     }
     #[test]
     fn p1_ex() {
-        assert_eq!(part1(EX).unwrap().as_str(), "95437")
+        assert_eq!(Day7.part1(EX).unwrap().to_string(), "95437")
     }
     #[test]
     fn p2_ex() {
-        assert_eq!(part2(EX).unwrap().as_str(), "24933642")
+        assert_eq!(Day7.part2(EX).unwrap().to_string(), "24933642")
+    }
+
+    #[test]
+    fn repl_commands() {
+        let fs = parse(EX).unwrap();
+        let ctx = RunContext::default();
+        assert_eq!(
+            ls(&fs, &["/a"], &ctx).unwrap(),
+            "       584  e/\n     29116  f\n      2557  g\n     62596  h.lst\n"
+        );
+        assert!(ls(&fs, &["/a/f"], &ctx).is_err());
+        assert_eq!(
+            du(&fs, &["100000"], &ctx).unwrap(),
+            "     94853  a\n       584  e\n"
+        );
     }
 }
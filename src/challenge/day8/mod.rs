@@ -4,42 +4,72 @@ use aoc::grid::fixed_grid::FixedGrid;
 use aoc::grid::grid_types::GridWidth;
 use aoc::Point;
 
-pub fn part1(input: &str) -> Result<String> {
-    let grid = aoc::grid::fixed_grid::FixedGrid::parse_ascii_grid(input, char_to_int)
-        .context("could not parse input grid")?;
+use super::{Answer, Day, Example, Expected, RunContext};
+
+pub struct Day8;
+
+impl Day for Day8 {
+    type Input = FixedGrid<i64>;
+
+    fn name(&self) -> &'static str {
+        "Treetop Tree House"
+    }
+    fn day(&self) -> u32 {
+        8
+    }
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+    fn solve_p1(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        Ok(solve_p1(input).into())
+    }
+    fn solve_p2(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        solve_p2(input).map(Answer::from)
+    }
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../../input/day8_ex"),
+            expected: Expected {
+                part1: Some("21"),
+                part2: Some("8"),
+            },
+        }]
+    }
+
+    fn generate(&self, rng: &mut fastrand::Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
+}
+
+pub fn parse(input: &str) -> Result<FixedGrid<i64>> {
+    FixedGrid::parse_ascii_grid(input, char_to_int).context("could not parse input grid")
+}
+
+pub fn solve_p1(grid: &FixedGrid<i64>) -> i64 {
     log::debug!("\n{}", grid);
 
-    let views = build_view_tree(&grid);
+    let views = build_view_tree(grid);
     log::debug!("\n{}", views);
 
-    let visible_trees = grid
-        .points()
+    grid.points()
         .filter(|pt| {
             let h = grid[*pt];
             let view = &views[*pt];
             h > view.min_view()
         })
-        .count();
-
-    Ok(format!("{:?}", visible_trees))
+        .count() as i64
 }
 
-pub fn part2(input: &str) -> Result<String> {
-    let grid = aoc::grid::fixed_grid::FixedGrid::parse_ascii_grid(input, char_to_int)
-        .context("could not parse input grid")?;
-
+pub fn solve_p2(grid: &FixedGrid<i64>) -> Result<i64> {
     log::debug!("trees\n{}", grid);
 
     // let s = all_senic_scores(&grid);
     // log::debug!("senic\n{}", s);
 
-    let best_score = grid
-        .points()
-        .map(|pt| senic_score(&grid, pt))
+    grid.points()
+        .map(|pt| senic_score(grid, pt))
         .max()
-        .ok_or_else(|| anyhow::anyhow!("no trees had a positive senic score"))?;
-
-    Ok(format!("{:?}", best_score))
+        .ok_or_else(|| anyhow::anyhow!("no trees had a positive senic score"))
 }
 
 fn iterate_sightline(
@@ -160,9 +190,23 @@ fn char_to_int(c: char) -> Result<i64> {
     }
 }
 
+/// A `size` by `size` forest
+pub fn generate(rng: &mut fastrand::Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            (0..size.max(1))
+                .map(|_| rng.digit(10))
+                .chain(Some('\n'))
+                .collect::<String>()
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::challenge::{oracle, Solution};
+    use proptest::{collection::vec, strategy::Strategy};
     const INPUT: &str = include_str!("../../../input/day8");
     const EX: &str = include_str!("../../../input/day8_ex");
 
@@ -176,21 +220,57 @@ mod tests {
         assert!(char_to_int('a').is_err());
     }
 
+    /// Small forests, with few enough heights that trees often tie
+    fn forests() -> impl Strategy<Value = String> {
+        (1..8usize, 1..8usize)
+            .prop_flat_map(|(width, height)| vec(vec(0..4u32, width), height))
+            .prop_map(|rows| {
+                rows.iter()
+                    .map(|r| r.iter().map(|h| h.to_string()).collect::<String>() + "\n")
+                    .collect()
+            })
+    }
+
+    #[test]
+    fn view_tree_matches_sightlines() {
+        oracle::check(
+            forests(),
+            |input| {
+                let trees = parse(input).unwrap();
+                let views = build_view_tree(&trees);
+                trees
+                    .points()
+                    .map(|pt| trees[pt] > views[pt].min_view())
+                    .collect::<Vec<_>>()
+            },
+            |input| {
+                let trees = parse(input).unwrap();
+                trees
+                    .points()
+                    .map(|pt| {
+                        Direction::iter()
+                            .any(|d| iterate_sightline(&trees, pt, d).all(|h| h < trees[pt]))
+                    })
+                    .collect()
+            },
+        );
+    }
+
     #[test]
     fn verify_p1() {
-        assert_eq!(part1(INPUT).unwrap().as_str(), "1825")
+        assert_eq!(Day8.part1(INPUT).unwrap().to_string(), "1825")
     }
     #[test]
     fn verify_p2() {
-        assert_eq!(part2(INPUT).unwrap().as_str(), "235200")
+        assert_eq!(Day8.part2(INPUT).unwrap().to_string(), "235200")
     }
 
     #[test]
     fn p1_ex() {
-        assert_eq!(part1(EX).unwrap().as_str(), "21")
+        assert_eq!(Day8.part1(EX).unwrap().to_string(), "21")
     }
     #[test]
     fn p2_ex() {
-        assert_eq!(part2(EX).unwrap().as_str(), "8")
+        assert_eq!(Day8.part2(EX).unwrap().to_string(), "8")
     }
 }
@@ -1,60 +1,87 @@
 use std::collections::HashSet;
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use aoc::{grid::compass::Direction, Point};
 
-pub fn part1(input: &str) -> Result<String> {
-    let x = move_rope(input, 1)?;
-    Ok(format!("{:?}", x))
-}
+use super::{
+    parse_error::{Locate, ParseError},
+    parser::{integer, lines, template},
+    Answer, Day, Example, Expected, RunContext,
+};
+
+pub struct Day9;
+
+impl Day for Day9 {
+    type Input = Vec<Instruction>;
+
+    fn name(&self) -> &'static str {
+        "Rope Bridge"
+    }
+    fn day(&self) -> u32 {
+        9
+    }
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+    fn solve_p1(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        Ok(move_rope(input, 1).into())
+    }
+    fn solve_p2(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        Ok(move_rope(input, 9).into())
+    }
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../../input/day9_ex"),
+            expected: Expected {
+                part1: Some("13"),
+                part2: Some("1"),
+            },
+        }]
+    }
 
-pub fn part2(input: &str) -> Result<String> {
-    let x = move_rope(input, 9)?;
-    Ok(format!("{:?}", x))
+    fn generate(&self, rng: &mut fastrand::Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
 }
 
-fn move_rope(input: &str, tail_len: usize) -> Result<usize> {
-    let instructions = parse(input)?;
+pub fn move_rope(instructions: &[Instruction], tail_len: usize) -> usize {
     log::debug!("{:#?}", instructions);
     let mut rope = Rope::new(tail_len);
     let mut seen = HashSet::new();
-    for d in instruction_moves(&instructions) {
+    for d in instruction_moves(instructions) {
         let tail = rope.move_head(d);
         seen.insert(tail);
         log::debug!("{:?}", d);
         log::trace!("{:#?}", rope);
     }
-    Ok(seen.len())
+    seen.len()
 }
 
-fn parse(input: &str) -> Result<Vec<Instruction>> {
-    input
-        .lines()
-        .map(|l| {
-            parse_single_instruction(l)
-                .with_context(|| format!("could not parse instruction: {:?}", l))
-        })
-        .collect()
+pub fn parse(input: &str) -> Result<Vec<Instruction>> {
+    lines(input, |l| parse_single_instruction(l.trim_end()))
 }
 
 fn parse_single_instruction(input: &str) -> Result<Instruction> {
-    let mut split = input.split_whitespace();
-    let direction_str = aoc::parse::expect_word(&mut split, "direction")?;
-    let count: usize = aoc::parse::expect_parse(&mut split, "count")?;
+    let [direction_str, count] = template(input, "{} {}")?;
+    let count = integer(count, "count").locate(input, count)?;
     let dir = match direction_str {
         "U" => Direction::North,
         "D" => Direction::South,
         "L" => Direction::West,
         "R" => Direction::East,
-        _ => anyhow::bail!("can not parse {:?} as a direction", direction_str),
+        _ => anyhow::bail!(ParseError::at(
+            input,
+            direction_str,
+            format!("can not parse {:?} as a direction", direction_str)
+        )),
     };
     Ok(Instruction { dir, count })
 }
 
 #[derive(Debug)]
-struct Instruction {
-    dir: Direction,
-    count: usize,
+pub struct Instruction {
+    pub dir: Direction,
+    pub count: usize,
 }
 
 fn instruction_moves(instr: &[Instruction]) -> impl Iterator<Item = Direction> + '_ {
@@ -98,23 +125,41 @@ fn adjust_tail(head: Point<i64>, tail: Point<i64>) -> Point<i64> {
     }
 }
 
+/// `size` head movements
+pub fn generate(rng: &mut fastrand::Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let dir = ['U', 'D', 'L', 'R'][rng.usize(..4)];
+            format!("{} {}\n", dir, rng.u32(1..=20))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::challenge::Solution;
     const INPUT: &str = include_str!("../../../input/day9");
     const EX: &str = include_str!("../../../input/day9_ex");
 
+    #[test]
+    fn direction_error_location() {
+        let e = parse("R 4\nU 2\nX 3\n").unwrap_err();
+        let e = e.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((e.line, e.column, e.snippet.as_str()), (3, 1, "X 3"));
+    }
+
     #[test]
     fn verify_p1() {
-        assert_eq!(part1(INPUT).unwrap().as_str(), "6384")
+        assert_eq!(Day9.part1(INPUT).unwrap().to_string(), "6384")
     }
     #[test]
     fn verify_p2() {
-        assert_eq!(part2(INPUT).unwrap().as_str(), "2734")
+        assert_eq!(Day9.part2(INPUT).unwrap().to_string(), "2734")
     }
 
     #[test]
     fn p1_ex() {
-        assert_eq!(part1(EX).unwrap().as_str(), "13")
+        assert_eq!(Day9.part1(EX).unwrap().to_string(), "13")
     }
 }
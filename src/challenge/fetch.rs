@@ -0,0 +1,202 @@
+use std::{fs, path, time};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+pub const YEAR: u32 = 2022;
+pub const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+pub fn run_fetch(args: &clap::ArgMatches) -> Result<()> {
+    let fetcher = InputFetcher::new(
+        args.value_of("input_dir").unwrap(),
+        args.value_of("base_url").unwrap(),
+        args.value_of("session"),
+    );
+    let days = args
+        .values_of("day")
+        .unwrap()
+        .map(|d| d.parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()?;
+    for day in days {
+        match fetcher.fetch(day)? {
+            Fetched::Cached(p) => println!("{} (cached)", p.display()),
+            Fetched::Downloaded(p) => println!("{} (downloaded)", p.display()),
+        }
+    }
+    Ok(())
+}
+
+/// Where a day's input came from
+#[derive(Debug, PartialEq)]
+pub enum Fetched {
+    Cached(path::PathBuf),
+    Downloaded(path::PathBuf),
+}
+
+/// Written next to each downloaded input, as `day{N}.meta.json`
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct InputMeta {
+    pub day: u32,
+    pub url: String,
+    /// Seconds since the unix epoch
+    pub fetched_at: u64,
+    pub bytes: usize,
+}
+
+/// Downloads puzzle inputs into the input directory, at most once per day
+pub struct InputFetcher {
+    input_dir: path::PathBuf,
+    base_url: String,
+    session: Option<String>,
+}
+
+impl InputFetcher {
+    pub fn new<P: Into<path::PathBuf>>(
+        input_dir: P,
+        base_url: &str,
+        session: Option<&str>,
+    ) -> InputFetcher {
+        InputFetcher {
+            input_dir: input_dir.into(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.map(|s| s.trim().to_string()),
+        }
+    }
+
+    fn url(&self, day: u32) -> String {
+        format!("{}/{}/day/{}/input", self.base_url, YEAR, day)
+    }
+
+    pub fn meta_path(&self, day: u32) -> path::PathBuf {
+        self.input_dir.join(format!("day{}.meta.json", day))
+    }
+
+    /// Path to the input for `day`, downloading it first if it is not cached.
+    /// The empty placeholders created by `new-day` do not count as cached.
+    pub fn fetch(&self, day: u32) -> Result<Fetched> {
+        let input_path = super::input_path(&self.input_dir, day, false);
+        let cached = fs::metadata(&input_path).is_ok_and(|m| m.len() > 0);
+        if cached {
+            log::debug!("using cached input: {}", input_path.display());
+            return Ok(Fetched::Cached(input_path));
+        }
+
+        let session = self.session.as_deref().ok_or_else(|| {
+            anyhow::anyhow!("a session token is required to download day {}", day)
+        })?;
+        let url = self.url(day);
+        log::info!("downloading {}", url);
+        let body = download(&url, session)
+            .with_context(|| format!("could not download input for day {}", day))?;
+        if body.is_empty() {
+            anyhow::bail!("server returned an empty input for day {}", day)
+        }
+
+        fs::create_dir_all(&self.input_dir)
+            .with_context(|| format!("Unable to create directory: {}", self.input_dir.display()))?;
+        let fetched_at = time::SystemTime::now()
+            .duration_since(time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let meta = InputMeta {
+            day,
+            url,
+            fetched_at,
+            bytes: body.len(),
+        };
+        write_atomic(&self.meta_path(day), &serde_json::to_string_pretty(&meta)?)?;
+        write_atomic(&input_path, &body)?;
+        Ok(Fetched::Downloaded(input_path))
+    }
+}
+
+fn download(url: &str, session: &str) -> Result<String> {
+    let response = ureq::get(url)
+        .set("Cookie", &format!("session={}", session))
+        .set("User-Agent", USER_AGENT)
+        .call();
+    match response {
+        Ok(r) => Ok(r.into_string()?),
+        Err(ureq::Error::Status(code, r)) => {
+            let body = r.into_string().unwrap_or_default();
+            anyhow::bail!("{} returned {}: {}", url, code, body.trim())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Write through a temporary file, so an interrupted download never looks cached
+fn write_atomic(path: &path::Path, content: &str) -> Result<()> {
+    let tmp = path.with_extension("part");
+    fs::write(&tmp, content).with_context(|| format!("Unable to write: {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("Unable to write: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::challenge::test_server::serve;
+
+    fn temp_dir(name: &str) -> path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-fetch-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn download_once() {
+        let (base_url, requests) = serve(vec![(200, "1000\n2000\n")]);
+        let dir = temp_dir("once");
+        let fetcher = InputFetcher::new(&dir, &base_url, Some("abc123"));
+
+        let input = dir.join("day1");
+        assert_eq!(
+            fetcher.fetch(1).unwrap(),
+            Fetched::Downloaded(input.clone())
+        );
+        let request = requests.recv().unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.url, "/2022/day/1/input");
+        assert_eq!(request.cookie, "session=abc123");
+        assert_eq!(fs::read_to_string(&input).unwrap(), "1000\n2000\n");
+        let meta: InputMeta =
+            serde_json::from_str(&fs::read_to_string(fetcher.meta_path(1)).unwrap()).unwrap();
+        assert_eq!(meta.url, format!("{}/2022/day/1/input", base_url));
+        assert_eq!(meta.bytes, 10);
+
+        // the stand-in server only answers once, so this has to come from the cache
+        assert_eq!(fetcher.fetch(1).unwrap(), Fetched::Cached(input));
+        assert!(requests.recv().is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn placeholder_is_replaced() {
+        let (base_url, _requests) = serve(vec![(200, "noop\n")]);
+        let dir = temp_dir("placeholder");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day10"), "").unwrap();
+        let fetcher = InputFetcher::new(&dir, &base_url, Some("abc123"));
+        assert!(matches!(fetcher.fetch(10).unwrap(), Fetched::Downloaded(_)));
+        assert_eq!(fs::read_to_string(dir.join("day10")).unwrap(), "noop\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn failed_download_is_not_cached() {
+        let (base_url, _requests) = serve(vec![(
+            404,
+            "Please don't repeatedly request this endpoint before it unlocks!",
+        )]);
+        let dir = temp_dir("failed");
+        let fetcher = InputFetcher::new(&dir, &base_url, Some("abc123"));
+        let e = fetcher.fetch(25).unwrap_err();
+        assert!(format!("{:#}", e).contains("404"), "{:#}", e);
+        assert!(!dir.join("day25").exists());
+        assert!(!fetcher.meta_path(25).exists());
+
+        let fetcher = InputFetcher::new(&dir, &base_url, None);
+        assert!(fetcher.fetch(25).is_err());
+        // nothing may have been written, so the directory might not exist
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,71 @@
+use std::{fs, io::Write};
+
+use anyhow::{Context, Result};
+
+use super::Solution;
+
+pub fn run_generate(args: &clap::ArgMatches) -> Result<()> {
+    let day = args.value_of("day").unwrap().parse::<u32>()?;
+    let seed = args.value_of("seed").unwrap().parse::<u64>()?;
+    let size = args.value_of("size").unwrap().parse::<usize>()?;
+    let solution = super::get_solution(day)
+        .ok_or_else(|| anyhow::anyhow!("unimplemented challenge day {}", day))?;
+    let input = generate(solution, seed, size)?;
+
+    match args.value_of("output") {
+        Some(path) => {
+            fs::write(path, &input).with_context(|| format!("Unable to write: {}", path))?;
+            log::info!("wrote {} bytes to {}", input.len(), path);
+        }
+        None => std::io::stdout().write_all(input.as_bytes())?,
+    }
+    Ok(())
+}
+
+/// The day's input for `seed`, the same seed and size always give the same input
+pub fn generate(solution: &dyn Solution, seed: u64, size: usize) -> Result<String> {
+    let mut rng = fastrand::Rng::with_seed(seed);
+    solution
+        .generate(&mut rng, size)
+        .ok_or_else(|| anyhow::anyhow!("day {} has no input generator", solution.day()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::challenge::{solutions, RunContext};
+
+    #[test]
+    fn generated_inputs_solve() {
+        let ctx = RunContext::default();
+        for solution in solutions() {
+            for seed in 0..3 {
+                let input = generate(*solution, seed, 20).unwrap();
+                let parsed = solution
+                    .parse(&input)
+                    .unwrap_or_else(|e| panic!("day {} seed {}: {:#}", solution.day(), seed, e));
+                for part in [1, 2] {
+                    if let Err(e) = solution.solve_parsed(part, &parsed, &ctx) {
+                        panic!(
+                            "day {} part {} seed {}: {:#}\n{}",
+                            solution.day(),
+                            part,
+                            seed,
+                            e,
+                            input
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn seeds_are_reproducible() {
+        for solution in solutions() {
+            let input = generate(*solution, 7, 10).unwrap();
+            assert_eq!(input, generate(*solution, 7, 10).unwrap());
+            assert_ne!(input, generate(*solution, 8, 10).unwrap());
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use std::{collections::BTreeMap, fmt::Write, process, time};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{jsonl::JsonLines, report::Outcome};
+
+/// Relative change in solve time worth pointing out
+const SIGNIFICANT_CHANGE: f64 = 0.1;
+
+pub fn run_history(args: &clap::ArgMatches) -> Result<()> {
+    let day = args.value_of("day").map(str::parse::<u32>).transpose()?;
+    let part = args.value_of("part").map(str::parse::<u32>).transpose()?;
+    let limit = args.value_of("limit").unwrap().parse::<usize>()?;
+    let records = JsonLines::<RunRecord>::new(args.value_of("runs").unwrap()).load()?;
+    print!("{}", trends(&records, day, part, limit));
+    Ok(())
+}
+
+/// One solved day/part, as stored in the run log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// RFC 3339, UTC
+    pub at: String,
+    /// Checked out commit, suffixed with `-dirty` when there were local changes
+    pub commit: Option<String>,
+    pub day: u32,
+    pub part: u32,
+    pub input: Option<String>,
+    pub input_digest: Option<String>,
+    pub answer: Option<String>,
+    pub error: Option<String>,
+    pub read_secs: f64,
+    pub parse_secs: f64,
+    pub solve_secs: f64,
+}
+
+impl RunRecord {
+    pub fn new(outcome: &Outcome, at: &str, commit: Option<&str>) -> RunRecord {
+        let (answer, error) = match &outcome.answer {
+            Ok(a) => (Some(a.to_string()), None),
+            Err(e) => (None, Some(format!("{:#}", e))),
+        };
+        RunRecord {
+            at: at.to_string(),
+            commit: commit.map(str::to_string),
+            day: outcome.day,
+            part: outcome.part,
+            input: outcome.input.as_ref().map(|p| p.display().to_string()),
+            input_digest: outcome.digest.clone(),
+            answer,
+            error,
+            read_secs: outcome.read.as_secs_f64(),
+            parse_secs: outcome.parse.as_secs_f64(),
+            solve_secs: outcome.solve.as_secs_f64(),
+        }
+    }
+
+    fn summary(&self) -> String {
+        match (&self.answer, &self.error) {
+            (Some(answer), _) => {
+                let mut lines = answer.lines();
+                let first = lines.next().unwrap_or("").to_string();
+                if lines.next().is_some() {
+                    first + " ..."
+                } else {
+                    first
+                }
+            }
+            (None, Some(e)) => format!("error: {}", e),
+            (None, None) => "error".to_string(),
+        }
+    }
+}
+
+/// SHA-256 of the input, so runs on the same input can be compared
+pub fn digest(input: &str) -> String {
+    format!("{:x}", Sha256::digest(input.as_bytes()))
+}
+
+/// The checked out commit, or `None` outside of a git checkout
+fn current_commit() -> Option<String> {
+    let git = |args: &[&str]| {
+        let output = process::Command::new("git").args(args).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8(output.stdout).ok()
+    };
+    let commit = git(&["rev-parse", "--short=12", "HEAD"])?
+        .trim()
+        .to_string();
+    let dirty = git(&["status", "--porcelain", "--untracked-files=no"])
+        .is_some_and(|s| !s.trim().is_empty());
+    Some(if dirty {
+        format!("{}-dirty", commit)
+    } else {
+        commit
+    })
+}
+
+/// Append the outcomes to the run log. A run log that can't be written is only
+/// worth a warning, it shouldn't fail the run itself.
+pub fn record(path: &str, outcomes: &[Outcome]) {
+    let at = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let commit = current_commit();
+    let records = outcomes
+        .iter()
+        .map(|o| RunRecord::new(o, &at, commit.as_deref()))
+        .collect::<Vec<_>>();
+    if let Err(e) = JsonLines::new(path).append(&records) {
+        log::warn!("could not record the run: {:#}", e)
+    }
+}
+
+/// The last `limit` runs of each day/part, each compared to the previous run on the same input
+fn trends(records: &[RunRecord], day: Option<u32>, part: Option<u32>, limit: usize) -> String {
+    let mut groups = BTreeMap::<(u32, u32), Vec<&RunRecord>>::new();
+    for r in records {
+        let wanted = |filter: Option<u32>, value| filter.is_none() || filter == Some(value);
+        if wanted(day, r.day) && wanted(part, r.part) {
+            groups.entry((r.day, r.part)).or_default().push(r);
+        }
+    }
+
+    let mut out = String::new();
+    for ((day, part), runs) in groups {
+        writeln!(out, "day {} part {}", day, part).unwrap();
+        for (idx, run) in runs
+            .iter()
+            .enumerate()
+            .skip(runs.len().saturating_sub(limit))
+        {
+            let previous = runs[..idx]
+                .iter()
+                .rev()
+                .find(|r| r.input_digest == run.input_digest);
+            let digest = run.input_digest.as_deref().unwrap_or("-");
+            let line = format!(
+                "  {}  {:<18}  {:<8}  {:>10}  {:<20}  {}",
+                run.at,
+                run.commit.as_deref().unwrap_or("-"),
+                &digest[..digest.len().min(8)],
+                format!("{:.2?}", time::Duration::from_secs_f64(run.solve_secs)),
+                run.summary(),
+                compare(previous.copied(), run),
+            );
+            writeln!(out, "{}", line.trim_end()).unwrap();
+        }
+    }
+    out
+}
+
+fn compare(previous: Option<&RunRecord>, run: &RunRecord) -> String {
+    let previous = match previous {
+        Some(p) => p,
+        None => return String::new(),
+    };
+    let mut notes = Vec::new();
+    if previous.answer != run.answer {
+        notes.push(format!("answer changed from {}", previous.summary()));
+    }
+    if previous.solve_secs > 0.0 {
+        let change = run.solve_secs / previous.solve_secs - 1.0;
+        if change.abs() >= SIGNIFICANT_CHANGE {
+            notes.push(format!("{:+.0}% solve time", change * 100.0));
+        }
+    }
+    notes.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(at: &str, input: &str, answer: &str, solve_secs: f64) -> RunRecord {
+        RunRecord {
+            at: at.to_string(),
+            commit: Some("0123456789ab".to_string()),
+            day: 15,
+            part: 2,
+            input: Some("input/day15".to_string()),
+            input_digest: Some(digest(input)),
+            answer: Some(answer.to_string()),
+            error: None,
+            read_secs: 0.0,
+            parse_secs: 0.0,
+            solve_secs,
+        }
+    }
+
+    #[test]
+    fn input_digest() {
+        assert_eq!(
+            digest(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn compare_runs_on_the_same_input() {
+        let records = vec![
+            run("2022-12-15T08:00:00Z", "real", "11482462818989", 1.0),
+            run("2022-12-15T09:00:00Z", "example", "56000011", 0.001),
+            run("2022-12-16T08:00:00Z", "real", "11482462818989", 1.5),
+            run("2022-12-17T08:00:00Z", "real", "11482462818988", 1.52),
+        ];
+        let out = trends(&records, Some(15), None, 3);
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4, "{}", out);
+        assert_eq!(lines[0], "day 15 part 2");
+        assert!(lines[1].starts_with("  2022-12-15T09:00:00Z  0123456789ab"));
+        assert!(lines[1].ends_with("56000011"), "{}", lines[1]);
+        assert!(lines[2].ends_with("+50% solve time"), "{}", lines[2]);
+        assert!(!lines[2].contains("answer changed"), "{}", lines[2]);
+        assert!(
+            lines[3].ends_with("answer changed from 11482462818989"),
+            "{}",
+            lines[3]
+        );
+        assert_eq!(trends(&records, Some(14), None, 3), "");
+    }
+}
@@ -0,0 +1,54 @@
+use std::{fs, io::Write, marker::PhantomData, path};
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// An append-only file of records, one JSON object per line
+pub struct JsonLines<T> {
+    path: path::PathBuf,
+    record: PhantomData<T>,
+}
+
+impl<T: Serialize + DeserializeOwned> JsonLines<T> {
+    pub fn new<P: Into<path::PathBuf>>(path: P) -> JsonLines<T> {
+        JsonLines {
+            path: path.into(),
+            record: PhantomData,
+        }
+    }
+
+    /// Every record so far, oldest first. A missing file has no records.
+    pub fn load(&self) -> Result<Vec<T>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        super::read_to_string(&self.path)?
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(idx, l)| {
+                serde_json::from_str(l).with_context(|| {
+                    format!("{} line {}: invalid record", self.path.display(), idx + 1)
+                })
+            })
+            .collect()
+    }
+
+    pub fn append<'a, I>(&self, records: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'a T>,
+        T: 'a,
+    {
+        let mut lines = String::new();
+        for record in records {
+            lines.push_str(&serde_json::to_string(record)?);
+            lines.push('\n');
+        }
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut f| f.write_all(lines.as_bytes()))
+            .with_context(|| format!("Unable to write: {}", self.path.display()))
+    }
+}
@@ -1,63 +1,228 @@
 use anyhow::{anyhow as ah, Context, Result};
-use std::{fs, io::Read, path};
-
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
+use std::{fs, io::Read, path, time};
+
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+
+pub use answers::DEFAULT_ANSWERS_FILE;
+pub use context::{Param, RunContext};
+pub use parse_error::ParseError;
+pub use repl::{arg, Command, CommandHelp};
+pub use solution::{Answer, Day, Example, Expected, Parsed, Solution};
+
+mod answers;
+mod bench;
+mod context;
+mod fetch;
+mod generate;
+mod history;
+mod jsonl;
+#[cfg(test)]
+mod oracle;
+pub mod parse_error;
+pub mod parser;
+mod repl;
+mod report;
+mod run_all;
+mod scaffold;
+mod solution;
+mod submit;
+#[cfg(test)]
+mod test_server;
+mod watch;
+
+static SOLUTIONS: &[&dyn Solution] = &[
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15,
+];
+
+const DEFAULT_INPUT_DIR: &str = "input";
+
+/// Every implemented day, in order
+pub fn solutions() -> &'static [&'static dyn Solution] {
+    SOLUTIONS
+}
+
+pub fn get_solution(day: u32) -> Option<&'static dyn Solution> {
+    SOLUTIONS.iter().find(|s| s.day() == day).copied()
+}
+
+/// Parse arbitrary bytes as a day's input. Used by the fuzz targets, and by the
+/// tests replaying what they found: parsers may reject garbage, never panic on it.
+pub fn fuzz_parse(day: u32, data: &[u8]) -> Result<()> {
+    let solution = get_solution(day).ok_or_else(|| ah!("unimplemented challenge day {}", day))?;
+    let input = std::str::from_utf8(data).context("input is not utf-8")?;
+    solution.parse(input).map(|_| ())
+}
 
 pub fn run(args: &clap::ArgMatches) -> Result<()> {
+    match args.subcommand() {
+        ("run-all", Some(sub)) => run_all::run_all(sub),
+        ("bench", Some(sub)) => bench::run_bench(sub),
+        ("new-day", Some(sub)) => scaffold::run_new_day(sub),
+        ("fetch", Some(sub)) => fetch::run_fetch(sub),
+        ("generate", Some(sub)) => generate::run_generate(sub),
+        ("submit", Some(sub)) => submit::run_submit(sub),
+        ("history", Some(sub)) => history::run_history(sub),
+        ("repl", Some(sub)) => repl::run_repl(sub),
+        ("watch", Some(sub)) => watch::run_watch(sub),
+        _ => run_single(args),
+    }
+}
+
+fn run_single(args: &clap::ArgMatches) -> Result<()> {
     let day = args.value_of("day").unwrap().parse::<u32>()?;
     let part = args.value_of("part").unwrap().parse::<u32>()?;
-    let input = read_to_string(args.value_of("input").unwrap())?;
-    log::debug!("running day {}:{}", day, part);
-    let result = match (day, part) {
-        (1, 1) => day1::part1(&input),
-        (1, 2) => day1::part2(&input),
-        (2, 1) => day2::part1(&input),
-        (2, 2) => day2::part2(&input),
-        (3, 1) => day3::part1(&input),
-        (3, 2) => day3::part2(&input),
-        (4, 1) => day4::part1(&input),
-        (4, 2) => day4::part2(&input),
-        (5, 1) => day5::part1(&input),
-        (5, 2) => day5::part2(&input),
-        (6, 1) => day6::part1(&input),
-        (6, 2) => day6::part2(&input),
-        (7, 1) => day7::part1(&input),
-        (7, 2) => day7::part2(&input),
-        (8, 1) => day8::part1(&input),
-        (8, 2) => day8::part2(&input),
-        (9, 1) => day9::part1(&input),
-        (9, 2) => day9::part2(&input),
-        (10, 1) => day10::part1(&input),
-        (10, 2) => day10::part2(&input),
-        (11, 1) => day11::part1(&input),
-        (11, 2) => day11::part2(&input),
-        (12, 1) => day12::part1(&input),
-        (12, 2) => day12::part2(&input),
-        (13, 1) => day13::part1(&input),
-        (13, 2) => day13::part2(&input),
-        (14, 1) => day14::part1(&input),
-        (14, 2) => day14::part2(&input),
-        (15, 1) => day15::part1(&input),
-        (15, 2) => day15::part2(&input),
-        (d, p) => Err(ah!("unimplemented challenge day {} part {}", d, p)),
-    }?;
-    println!("{}", result);
-    Ok(())
+    let format = report::OutputFormat::from_args(args)?;
+    let solution = get_solution(day)
+        .ok_or_else(|| ah!("unimplemented challenge day {} part {}", day, part))?;
+    let source = input_source(args.value_of("input"), day, args.is_present("example"));
+
+    let start = time::Instant::now();
+    let input = read_input(source.as_deref())?;
+    let parsed = report::ParsedInput::parse(solution, source.as_deref(), &input, start.elapsed());
+    let ctx = run_context(solution, source.as_deref(), args.values_of("param"))?;
+    let mut outcome = report::Outcome::solve(solution, part, &parsed, &ctx);
+
+    if args.is_present("check") {
+        outcome.check(&answers::AnswerStore::load(
+            args.value_of("answers").unwrap(),
+        )?);
+    }
+
+    match format {
+        report::OutputFormat::Text => {
+            if let Ok(answer) = &outcome.answer {
+                println!("{}", answer);
+            }
+            if let Some(verdict) = &outcome.verdict {
+                eprintln!("{}", verdict);
+            }
+        }
+        report::OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&outcome.record())?);
+        }
+    }
+    if !args.is_present("no_record") {
+        history::record(
+            args.value_of("runs").unwrap(),
+            std::slice::from_ref(&outcome),
+        );
+    }
+    outcome.into_result()
+}
+
+/// Location of a day's puzzle input, following the `input/day{N}` / `input/day{N}_ex` convention
+pub fn input_path<P: AsRef<path::Path>>(input_dir: P, day: u32, example: bool) -> path::PathBuf {
+    let suffix = if example { "_ex" } else { "" };
+    input_dir.as_ref().join(format!("day{}{}", day, suffix))
+}
+
+/// Inputs following the `day{N}_ex` naming convention are puzzle examples
+fn is_example_input(source: Option<&path::Path>) -> bool {
+    source
+        .and_then(|p| p.file_name())
+        .is_some_and(|name| name.to_string_lossy().ends_with("_ex"))
+}
+
+/// Build the context for a run, applying `--param key=value` overrides
+fn run_context<'a, I>(
+    solution: &dyn Solution,
+    source: Option<&path::Path>,
+    overrides: Option<I>,
+) -> Result<RunContext>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut ctx = RunContext::new(is_example_input(source));
+    for param in overrides.into_iter().flatten() {
+        let (key, value) =
+            parse_param(solution, param).with_context(|| format!("invalid --param {:?}", param))?;
+        ctx = ctx.with_param(key, value);
+    }
+    Ok(ctx)
+}
+
+/// Split a `key=value` parameter override, checking that the day declares `key`
+fn parse_param<'a>(solution: &dyn Solution, param: &'a str) -> Result<(&'a str, &'a str)> {
+    let (key, value) = parser::key_value(param, '=')?;
+    let key = key.trim();
+    check_param(solution, key)?;
+    Ok((key, value.trim()))
+}
+
+/// Reject parameters the day does not declare, listing the ones it does
+fn check_param(solution: &dyn Solution, key: &str) -> Result<()> {
+    if solution.params().iter().any(|p| p.name == key) {
+        return Ok(());
+    }
+    let known = solution
+        .params()
+        .iter()
+        .map(|p| format!("\n  {}: {}", p.name, p.help))
+        .collect::<String>();
+    if known.is_empty() {
+        anyhow::bail!("day {} has no parameters, got {:?}", solution.day(), key)
+    }
+    anyhow::bail!(
+        "unknown parameter {:?} for day {}, expected one of:{}",
+        key,
+        solution.day(),
+        known
+    )
+}
+
+/// Resolve the input argument to a file, where `None` means stdin (`-`).
+/// When no input is given, fall back to the conventional path for the day.
+fn input_source(input: Option<&str>, day: u32, example: bool) -> Option<path::PathBuf> {
+    match input {
+        Some("-") => None,
+        Some(p) => Some(p.into()),
+        None => Some(input_path(DEFAULT_INPUT_DIR, day, example)),
+    }
+}
+
+fn read_input(source: Option<&path::Path>) -> Result<String> {
+    match source {
+        Some(p) => read_to_string(p),
+        None => read_stdin(),
+    }
+}
+
+fn read_stdin() -> Result<String> {
+    log::trace!("Reading content of stdin");
+    let mut result = String::new();
+    std::io::stdin()
+        .read_to_string(&mut result)
+        .context("Unable to read stdin")?;
+    Ok(result)
 }
 
 fn read_to_string<P: AsRef<path::Path>>(path: P) -> Result<String> {
@@ -70,3 +235,95 @@ fn read_to_string<P: AsRef<path::Path>>(path: P) -> Result<String> {
     f.read_to_string(&mut result)?;
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conventional_input_paths() {
+        assert_eq!(
+            input_path("input", 14, false),
+            path::PathBuf::from("input/day14")
+        );
+        assert_eq!(
+            input_path("input", 14, true),
+            path::PathBuf::from("input/day14_ex")
+        );
+    }
+
+    #[test]
+    fn example_inputs() {
+        assert!(is_example_input(Some(&input_path("input", 15, true))));
+        assert!(!is_example_input(Some(&input_path("input", 15, false))));
+        assert!(!is_example_input(None));
+    }
+
+    #[test]
+    fn param_overrides() {
+        let day15 = get_solution(15).unwrap();
+        let ctx = run_context(day15, None, Some(vec!["row=11", "max_coord = 30"])).unwrap();
+        assert_eq!(ctx.param("row", 0).unwrap(), 11);
+        assert_eq!(ctx.param("max_coord", 0).unwrap(), 30);
+        assert!(run_context(day15, None, Some(vec!["rows=11"])).is_err());
+        assert!(run_context(day15, None, Some(vec!["row"])).is_err());
+        assert!(run_context(get_solution(1).unwrap(), None, Some(vec!["row=1"])).is_err());
+    }
+
+    #[test]
+    fn params_are_unique() {
+        for s in solutions() {
+            let mut names = s.params().iter().map(|p| p.name).collect::<Vec<_>>();
+            names.sort_unstable();
+            names.dedup();
+            assert_eq!(names.len(), s.params().len(), "day {}", s.day());
+        }
+    }
+
+    #[test]
+    fn registry_is_ordered_by_day() {
+        let days = solutions().iter().map(|s| s.day()).collect::<Vec<_>>();
+        let expected = (1..=days.len() as u32).collect::<Vec<_>>();
+        assert_eq!(days, expected)
+    }
+
+    #[test]
+    fn registered_examples() {
+        for s in solutions() {
+            for ex in s.examples() {
+                for part in 1..=2 {
+                    if let Some(answer) = ex.expected.part(part) {
+                        assert_eq!(
+                            s.solve(part, ex.input, &RunContext::new(true))
+                                .unwrap()
+                                .to_string(),
+                            answer,
+                            "day {} part {}",
+                            s.day(),
+                            part
+                        )
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parsed_input_is_reused_across_parts() {
+        let s = get_solution(1).unwrap();
+        let parsed = s.parse(s.examples()[0].input).unwrap();
+        let ctx = RunContext::new(true);
+        assert_eq!(
+            s.solve_parsed(1, &parsed, &ctx).unwrap(),
+            Answer::Int(24000)
+        );
+        assert_eq!(
+            s.solve_parsed(2, &parsed, &ctx).unwrap(),
+            Answer::Int(45000)
+        );
+        assert!(get_solution(2)
+            .unwrap()
+            .solve_parsed(1, &parsed, &ctx)
+            .is_err());
+    }
+}
@@ -0,0 +1,56 @@
+//! Property tests pitting an optimized solver against a naive reference
+//! implementation. Each property lives in the tests of the module it checks
+//! and supplies its own proptest strategy for the inputs; currently day6's
+//! marker search, day8's view tree and day15's `MultiRange` have one.
+
+use std::fmt::Debug;
+
+use proptest::{
+    strategy::Strategy,
+    test_runner::{Config, TestCaseError, TestRunner},
+};
+
+/// Generated inputs checked per property
+const CASES: u32 = 512;
+
+/// Run `fast` and `naive` on inputs from `inputs` and panic with the smallest
+/// input they disagree on
+pub fn check<S, T, F, N>(inputs: S, fast: F, naive: N)
+where
+    S: Strategy,
+    S::Value: Debug,
+    T: PartialEq + Debug,
+    F: Fn(&S::Value) -> T,
+    N: Fn(&S::Value) -> T,
+{
+    let mut runner = TestRunner::new(Config {
+        cases: CASES,
+        failure_persistence: None,
+        ..Config::default()
+    });
+    let result = runner.run(&inputs, |input| {
+        let (expected, actual) = (naive(&input), fast(&input));
+        if expected == actual {
+            Ok(())
+        } else {
+            Err(TestCaseError::fail(format!(
+                "expected {:?} (naive), got {:?}",
+                expected, actual
+            )))
+        }
+    });
+    if let Err(e) = result {
+        panic!("{}", e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "minimal failing input: 10")]
+    fn reports_smallest_disagreement() {
+        check(0..1000u32, |x| x.min(&9) * 2, |x| x * 2);
+    }
+}
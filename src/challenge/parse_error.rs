@@ -0,0 +1,194 @@
+use std::fmt::Display;
+
+use anyhow::Result;
+
+/// A parse failure, located in the text that was being parsed
+///
+/// Errors are created relative to whatever slice a parser was handed (a line,
+/// a word), and moved outwards with `Locate::locate` until they point into the
+/// whole puzzle input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based byte offset into the line
+    pub column: usize,
+    /// The full line containing the error
+    pub snippet: String,
+    pub message: String,
+    offset: usize,
+    len: usize,
+}
+
+impl ParseError {
+    /// An error covering `span`, which should be a slice of `input`.
+    /// Any other `span` is reported at the start of `input`.
+    pub fn at(input: &str, span: &str, message: impl Display) -> ParseError {
+        let (offset, len) = match offset_in(input, span) {
+            Some(offset) => (offset, span.len()),
+            None => (0, 0),
+        };
+        let mut e = ParseError {
+            line: 1,
+            column: 1,
+            snippet: String::new(),
+            message: message.to_string(),
+            offset,
+            len,
+        };
+        e.place(input);
+        e
+    }
+
+    /// An error for input that ended too early
+    pub fn end_of(input: &str, message: impl Display) -> ParseError {
+        ParseError::at(input, &input[input.len()..], message)
+    }
+
+    /// Move an error located in `inner` so that it points into `outer`
+    fn shift(&mut self, outer: &str, inner: &str) {
+        match offset_in(outer, inner) {
+            Some(offset) => self.offset += offset,
+            None => {
+                self.offset = 0;
+                self.len = 0;
+            }
+        }
+        self.place(outer);
+    }
+
+    fn place(&mut self, input: &str) {
+        let before = &input[..self.offset];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        self.line = before.matches('\n').count() + 1;
+        self.column = self.offset - line_start + 1;
+        self.snippet = input[line_start..]
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string();
+    }
+
+    /// Show the offending line with the failing span underlined
+    ///
+    /// ```text
+    /// error: expected `from`, found `form`
+    ///  --> line 7, column 8
+    ///   |
+    /// 7 | move 1 form 2 to 1
+    ///   |        ^^^^
+    /// ```
+    pub fn render(&self) -> String {
+        let gutter = " ".repeat(self.line.to_string().len());
+        let start = (self.column - 1).min(self.snippet.len());
+        let end = (start + self.len).min(self.snippet.len());
+        let indent = self.snippet[..start].chars().count();
+        let width = self.snippet[start..end].chars().count().max(1);
+        format!(
+            "error: {}\n{}--> line {}, column {}\n{} |\n{} | {}\n{} | {}{}",
+            self.message,
+            gutter,
+            self.line,
+            self.column,
+            gutter,
+            self.line,
+            self.snippet,
+            gutter,
+            " ".repeat(indent),
+            "^".repeat(width)
+        )
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Byte offset of `span` within `input`, if it is a slice of it
+fn offset_in(input: &str, span: &str) -> Option<usize> {
+    let start = (span.as_ptr() as usize).checked_sub(input.as_ptr() as usize)?;
+    if start + span.len() <= input.len() {
+        Some(start)
+    } else {
+        None
+    }
+}
+
+pub trait Locate<T> {
+    /// Report a failure to parse `span` (a slice of `input`) at its position in `input`.
+    /// Errors that already carry a more precise location inside `span` keep it.
+    fn locate(self, input: &str, span: &str) -> Result<T>;
+}
+
+impl<T> Locate<T> for Result<T> {
+    fn locate(self, input: &str, span: &str) -> Result<T> {
+        self.map_err(|mut e| match e.downcast_mut::<ParseError>() {
+            Some(located) => {
+                located.shift(input, span);
+                e
+            }
+            None => ParseError::at(input, span, format!("{:#}", e)).into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "move 1 from 2 to 1\nmove 3 form 1 to 3\n";
+
+    #[test]
+    fn locate_span() {
+        let line = INPUT.lines().nth(1).unwrap();
+        let e = ParseError::at(INPUT, &line[7..11], "bad word");
+        assert_eq!((e.line, e.column), (2, 8));
+        assert_eq!(e.snippet, "move 3 form 1 to 3");
+        assert_eq!(e.to_string(), "line 2, column 8: bad word");
+    }
+
+    #[test]
+    fn locate_through_nested_slices() {
+        let line = INPUT.lines().nth(1).unwrap();
+        let word = line.split_whitespace().nth(2).unwrap();
+        let r: Result<()> = Err(ParseError::at(word, &word[1..], "bad letter").into());
+        let e = r
+            .locate(line, word)
+            .locate(INPUT, line)
+            .unwrap_err()
+            .downcast::<ParseError>()
+            .unwrap();
+        assert_eq!((e.line, e.column), (2, 9));
+    }
+
+    #[test]
+    fn unlocated_errors_point_at_span() {
+        let line = INPUT.lines().nth(1).unwrap();
+        let r: Result<()> = Err(anyhow::anyhow!("no good"));
+        let e = r.locate(INPUT, line).unwrap_err();
+        let e = e.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((e.line, e.column), (2, 1));
+        assert_eq!(e.message, "no good");
+    }
+
+    #[test]
+    fn render_caret() {
+        let e = ParseError::at(INPUT, &INPUT[26..30], "expected `from`, found `form`");
+        assert_eq!(
+            e.render(),
+            "error: expected `from`, found `form`\n \
+             --> line 2, column 8\n  \
+              |\n\
+             2 | move 3 form 1 to 3\n  \
+              |        ^^^^"
+        );
+    }
+}
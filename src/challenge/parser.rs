@@ -0,0 +1,295 @@
+//! Small parsing combinators shared by the days' parsers
+//!
+//! Every function takes the text to parse as a slice and returns slices of it,
+//! so failures are `ParseError`s pointing at the offending span. Errors are
+//! located relative to the slice a function was given; `Locate::locate` moves
+//! them out to the whole puzzle input.
+
+use std::{fmt::Display, str::FromStr};
+
+use anyhow::{Context, Result};
+
+use super::parse_error::{Locate, ParseError};
+
+/// Strip `literal` from the start of `input`, returning the rest
+pub fn literal<'a>(input: &'a str, literal: &str) -> Result<&'a str> {
+    if let Some(rest) = input.strip_prefix(literal) {
+        return Ok(rest);
+    }
+    let mismatch = input
+        .char_indices()
+        .zip(literal.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or(input.len(), |((idx, _), _)| idx);
+    // point at the whole word that should have been the literal
+    let start = input[..mismatch]
+        .rfind(char::is_whitespace)
+        .map_or(0, |idx| idx + 1);
+    let end = input[mismatch..]
+        .find(char::is_whitespace)
+        .map_or(input.len(), |idx| mismatch + idx);
+    let found = &input[start..end];
+    let message = if found.is_empty() {
+        format!("expected `{}`", literal.trim())
+    } else {
+        format!("expected `{}`, found `{}`", literal.trim(), found)
+    };
+    Err(ParseError::at(input, found, message).into())
+}
+
+/// Parse all of `input` as the integer described by `name`
+pub fn integer<T>(input: &str, name: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    input.parse::<T>().map_err(|e| {
+        let message = if input.is_empty() {
+            format!("expected {}", name)
+        } else {
+            format!("could not parse {}: {}", name, e)
+        };
+        ParseError::at(input, input, message).into()
+    })
+}
+
+/// Parse every `sep` separated item of `input`
+pub fn separated<'a, T, F>(input: &'a str, sep: &str, mut item: F) -> Result<Vec<T>>
+where
+    F: FnMut(&'a str) -> Result<T>,
+{
+    input.split(sep).map(|s| item(s).locate(input, s)).collect()
+}
+
+/// Parse every line of `input`
+pub fn lines<'a, T, F>(input: &'a str, mut line: F) -> Result<Vec<T>>
+where
+    F: FnMut(&'a str) -> Result<T>,
+{
+    input.lines().map(|l| line(l).locate(input, l)).collect()
+}
+
+/// A group of non-blank lines, as yielded by `records`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Record<'a> {
+    /// 1-based position of the record in the input
+    pub index: usize,
+    /// 1-based line number of the record's first line
+    pub line: usize,
+    /// The record's lines, without the line ending of the last one
+    pub text: &'a str,
+}
+
+impl<'a> Record<'a> {
+    pub fn lines(&self) -> std::str::Lines<'a> {
+        self.text.lines()
+    }
+}
+
+/// Stream the blank-line separated records of `input`
+///
+/// Any number of blank (or whitespace only) lines may separate records, or
+/// lead and trail the input, and lines may end in either `\n` or `\r\n`.
+pub fn records(input: &str) -> Records<'_> {
+    Records {
+        input,
+        offset: 0,
+        line: 0,
+        index: 0,
+    }
+}
+
+pub struct Records<'a> {
+    input: &'a str,
+    offset: usize,
+    line: usize,
+    index: usize,
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Record<'a>;
+
+    fn next(&mut self) -> Option<Record<'a>> {
+        let mut record: Option<(usize, usize)> = None;
+        let mut end = self.offset;
+        while self.offset < self.input.len() {
+            let rest = &self.input[self.offset..];
+            let len = rest.find('\n').map_or(rest.len(), |idx| idx + 1);
+            let content = rest[..len].trim_end_matches(['\n', '\r']);
+            let blank = content.trim().is_empty();
+            if blank && record.is_some() {
+                break;
+            }
+            self.line += 1;
+            if !blank {
+                record.get_or_insert((self.offset, self.line));
+                end = self.offset + content.len();
+            }
+            self.offset += len;
+        }
+        let (start, line) = record?;
+        self.index += 1;
+        Some(Record {
+            index: self.index,
+            line,
+            text: &self.input[start..end],
+        })
+    }
+}
+
+/// Parse every blank-line separated record of `input`, naming the record
+/// that failed
+pub fn each_record<'a, T, F>(input: &'a str, mut record: F) -> Result<Vec<T>>
+where
+    F: FnMut(Record<'a>) -> Result<T>,
+{
+    records(input)
+        .map(|r| {
+            record(r)
+                .locate(input, r.text)
+                .with_context(|| format!("record {} (starting on line {})", r.index, r.line))
+        })
+        .collect()
+}
+
+/// Split `key=value` (with `sep` in place of `=`) into the key and the value
+pub fn key_value(input: &str, sep: char) -> Result<(&str, &str)> {
+    input
+        .split_once(sep)
+        .ok_or_else(|| ParseError::at(input, input, format!("expected key{}value", sep)).into())
+}
+
+/// Match `input` against `pattern`, returning what each `{}` stood for
+///
+/// A field extends up to the first character of the literal text following
+/// it, or to the end of the input for a trailing field.
+///
+/// ```text
+/// let [count, src, dst] = template("move 1 from 2 to 1", "move {} from {} to {}")?;
+/// ```
+pub fn template<'a, const N: usize>(input: &'a str, pattern: &str) -> Result<[&'a str; N]> {
+    let mut pieces = pattern.split("{}");
+    if pattern.matches("{}").count() != N {
+        anyhow::bail!("template {:?} does not have {} fields", pattern, N)
+    }
+
+    let mut rest = literal(input, pieces.next().unwrap_or_default())?;
+    let mut fields = Vec::with_capacity(N);
+    for next in pieces {
+        let end = match next.chars().next() {
+            Some(c) => rest.find(c).unwrap_or(rest.len()),
+            None => rest.len(),
+        };
+        let (field, after) = rest.split_at(end);
+        if field.is_empty() {
+            anyhow::bail!(ParseError::at(input, field, "expected a value"))
+        }
+        fields.push(field);
+        rest = literal(after, next).locate(input, after)?;
+    }
+    if !rest.is_empty() {
+        anyhow::bail!(ParseError::at(
+            input,
+            rest,
+            format!("unexpected `{}`", rest)
+        ))
+    }
+    Ok(fields.try_into().unwrap_or_else(|_| unreachable!()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn located<T: std::fmt::Debug>(r: Result<T>) -> ParseError {
+        r.unwrap_err().downcast::<ParseError>().unwrap()
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(literal("move 3", "move ").unwrap(), "3");
+        let e = located(literal("mvoe 3", "move "));
+        assert_eq!(
+            (e.column, e.message.as_str()),
+            (1, "expected `move`, found `mvoe`")
+        );
+        let e = located(literal(" form 1", " from "));
+        assert_eq!(
+            (e.column, e.message.as_str()),
+            (2, "expected `from`, found `form`")
+        );
+        let e = located(literal("mov", "move "));
+        assert_eq!(
+            (e.column, e.message.as_str()),
+            (1, "expected `move`, found `mov`")
+        );
+        assert_eq!(located(literal("", "move")).message, "expected `move`");
+    }
+
+    #[test]
+    fn integers_and_lists() {
+        assert_eq!(integer::<i64>("-12", "x").unwrap(), -12);
+        assert!(integer::<usize>("-12", "x").is_err());
+        assert_eq!(located(integer::<i64>("", "x")).message, "expected x");
+
+        let items = separated("79, 98", ", ", |s| integer::<u32>(s, "item")).unwrap();
+        assert_eq!(items, vec![79, 98]);
+        let e = located(separated("79, x8, 1", ", ", |s| integer::<u32>(s, "item")));
+        assert_eq!(e.column, 5);
+
+        let input = "1\n2\nthree\n";
+        let e = located(lines(input, |l| integer::<u32>(l, "number")));
+        assert_eq!((e.line, e.column, e.snippet.as_str()), (3, 1, "three"));
+    }
+
+    #[test]
+    fn blank_line_records() {
+        let input = "1\n2\n\n3\n\n\n4\n";
+        let texts = records(input).map(|r| r.text).collect::<Vec<_>>();
+        assert_eq!(texts, vec!["1\n2", "3", "4"]);
+        assert_eq!(records("").count(), 0);
+
+        let input = "\n1\r\n2\r\n  \r\n\r\n3\n4";
+        let found = records(input)
+            .map(|r| (r.index, r.line, r.lines().collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec![(1, 2, vec!["1", "2"]), (2, 6, vec!["3", "4"])]);
+        assert_eq!(records("\n \n").count(), 0);
+
+        let e =
+            each_record("1\n\n2\nx\n", |r| lines(r.text, |l| integer::<u32>(l, "n"))).unwrap_err();
+        assert_eq!(e.to_string(), "record 2 (starting on line 3)");
+        let e = e.downcast::<ParseError>().unwrap();
+        assert_eq!((e.line, e.column, e.snippet.as_str()), (4, 1, "x"));
+    }
+
+    #[test]
+    fn key_values() {
+        assert_eq!(key_value("row=10", '=').unwrap(), ("row", "10"));
+        assert_eq!(key_value("a=b=c", '=').unwrap(), ("a", "b=c"));
+        assert!(key_value("row", '=').is_err());
+    }
+
+    #[test]
+    fn templates() {
+        let [count, src, dst] = template("move 1 from 22 to 3", "move {} from {} to {}").unwrap();
+        assert_eq!((count, src, dst), ("1", "22", "3"));
+
+        let line = "Sensor at x=-2, y=18: closest";
+        let [x, y] = template(line, "Sensor at x={}, y={}: closest").unwrap();
+        assert_eq!((x, y), ("-2", "18"));
+
+        let e = located(template::<3>("move 3 form 1 to 3", "move {} from {} to {}"));
+        assert_eq!(
+            (e.column, e.message.as_str()),
+            (8, "expected `from`, found `form`")
+        );
+        let e = located(template::<3>("move 3 from 1", "move {} from {} to {}"));
+        assert!(e.message.starts_with("expected"), "{}", e.message);
+        let e = located(template::<1>("move  from", "move {} from"));
+        assert_eq!((e.column, e.message.as_str()), (6, "expected a value"));
+        let e = located(template::<1>("x=1; y", "x={};"));
+        assert_eq!((e.column, e.message.as_str()), (5, "unexpected ` y`"));
+        assert!(template::<2>("x=1", "x={}").is_err());
+    }
+}
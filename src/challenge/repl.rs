@@ -0,0 +1,308 @@
+use std::{fmt::Display, path, str::FromStr, time};
+
+use anyhow::{Context, Result};
+use rustyline::{
+    completion::Completer, error::ReadlineError, highlight::Highlighter, hint::Hinter,
+    history::DefaultHistory, validate::Validator, Editor, Helper,
+};
+
+use super::{Parsed, RunContext, Solution};
+
+const HISTORY_FILE: &str = ".repl_history";
+
+/// A command `repl` offers for one day, run against that day's parsed input
+pub struct Command<I: 'static> {
+    pub name: &'static str,
+    /// Argument synopsis, e.g. `<row>`
+    pub args: &'static str,
+    pub help: &'static str,
+    pub run: fn(&I, &[&str], &RunContext) -> Result<String>,
+}
+
+/// The type-erased part of a `Command`, for listing and completion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandHelp {
+    pub name: &'static str,
+    pub args: &'static str,
+    pub help: &'static str,
+}
+
+impl<I> Command<I> {
+    pub fn describe(&self) -> CommandHelp {
+        CommandHelp {
+            name: self.name,
+            args: self.args,
+            help: self.help,
+        }
+    }
+}
+
+/// The `idx`th argument of a command, parsed as `T`
+pub fn arg<T>(args: &[&str], idx: usize, name: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let value = args
+        .get(idx)
+        .ok_or_else(|| anyhow::anyhow!("missing argument <{}>", name))?;
+    value
+        .parse::<T>()
+        .map_err(|e| anyhow::anyhow!("{}", e))
+        .with_context(|| format!("invalid <{}>: {:?}", name, value))
+}
+
+const BUILTINS: &[CommandHelp] = &[
+    CommandHelp {
+        name: "solve",
+        args: "<part>",
+        help: "solve a part of the loaded input",
+    },
+    CommandHelp {
+        name: "set",
+        args: "<key>=<value>",
+        help: "override a puzzle parameter for `solve`",
+    },
+    CommandHelp {
+        name: "params",
+        args: "",
+        help: "list the day's puzzle parameters",
+    },
+    CommandHelp {
+        name: "reload",
+        args: "",
+        help: "read and parse the input again",
+    },
+    CommandHelp {
+        name: "help",
+        args: "",
+        help: "list commands",
+    },
+    CommandHelp {
+        name: "quit",
+        args: "",
+        help: "leave the repl",
+    },
+];
+
+pub fn run_repl(args: &clap::ArgMatches) -> Result<()> {
+    let day = args.value_of("day").unwrap().parse::<u32>()?;
+    let solution = super::get_solution(day)
+        .ok_or_else(|| anyhow::anyhow!("unimplemented challenge day {}", day))?;
+    let source = super::input_source(args.value_of("input"), day, args.is_present("example"))
+        .ok_or_else(|| anyhow::anyhow!("the repl reads commands from stdin, give an input file"))?;
+    let ctx = super::run_context(solution, Some(&source), args.values_of("param"))?;
+    let mut repl = Repl::load(solution, source, ctx)?;
+
+    let mut editor = Editor::<CommandCompleter, DefaultHistory>::new()?;
+    editor.set_helper(Some(CommandCompleter {
+        names: repl.commands().iter().map(|c| c.name).collect(),
+    }));
+    if editor.load_history(HISTORY_FILE).is_err() {
+        log::debug!("no repl history yet");
+    }
+    println!(
+        "day {}: {} (`help` lists commands)",
+        solution.day(),
+        solution.name()
+    );
+    let prompt = format!("day{}> ", solution.day());
+    loop {
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        if !line.trim().is_empty() {
+            editor.add_history_entry(line.as_str())?;
+        }
+        match repl.eval(&line) {
+            Ok(Step::Print(out)) => println!("{}", out.trim_end()),
+            Ok(Step::Quit) => break,
+            Err(e) => eprintln!("error: {:#}", e),
+        }
+    }
+    editor
+        .save_history(HISTORY_FILE)
+        .with_context(|| format!("Unable to write: {}", HISTORY_FILE))
+}
+
+enum Step {
+    Print(String),
+    Quit,
+}
+
+/// A day's input, parsed once and queried by commands
+struct Repl<'a> {
+    solution: &'a dyn Solution,
+    source: path::PathBuf,
+    ctx: RunContext,
+    parsed: Parsed,
+}
+
+impl<'a> Repl<'a> {
+    fn load(solution: &'a dyn Solution, source: path::PathBuf, ctx: RunContext) -> Result<Self> {
+        let parsed = solution.parse(&super::read_input(Some(&source))?)?;
+        Ok(Repl {
+            solution,
+            source,
+            ctx,
+            parsed,
+        })
+    }
+
+    fn commands(&self) -> Vec<CommandHelp> {
+        let mut commands = self.solution.commands();
+        commands.extend_from_slice(BUILTINS);
+        commands
+    }
+
+    fn eval(&mut self, line: &str) -> Result<Step> {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let (name, args) = match words.split_first() {
+            Some((name, args)) => (*name, args),
+            None => return Ok(Step::Print(String::new())),
+        };
+        if let Some(out) = self
+            .solution
+            .run_command(&self.parsed, name, args, &self.ctx)
+        {
+            return out.map(Step::Print);
+        }
+        let out = match name {
+            "solve" => {
+                let part = arg::<u32>(args, 0, "part")?;
+                let start = time::Instant::now();
+                let answer = self.solution.solve_parsed(part, &self.parsed, &self.ctx)?;
+                format!(
+                    "{}\n({:.2?})",
+                    answer.to_string().trim_end(),
+                    start.elapsed()
+                )
+            }
+            "set" => {
+                let param = args.join(" ");
+                let (key, value) = super::parse_param(self.solution, &param)?;
+                self.ctx = self.ctx.clone().with_param(key, value);
+                format!("{} = {}", key, value)
+            }
+            "params" => self
+                .solution
+                .params()
+                .iter()
+                .map(|p| format!("{}: {}\n", p.name, p.help))
+                .collect(),
+            "reload" => {
+                self.parsed = self
+                    .solution
+                    .parse(&super::read_input(Some(&self.source))?)?;
+                format!("reloaded {}", self.source.display())
+            }
+            "help" => self
+                .commands()
+                .iter()
+                .map(|c| format!("{:<24} {}\n", format!("{} {}", c.name, c.args), c.help))
+                .collect(),
+            "quit" | "exit" => return Ok(Step::Quit),
+            _ => anyhow::bail!("unknown command {:?}, try `help`", name),
+        };
+        Ok(Step::Print(out))
+    }
+}
+
+/// Command names starting with what was typed so far, only for the first word
+fn complete(names: &[&str], typed: &str) -> Vec<String> {
+    if typed.contains(char::is_whitespace) {
+        return Vec::new();
+    }
+    let mut candidates = names
+        .iter()
+        .filter(|n| n.starts_with(typed))
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>();
+    candidates.sort();
+    candidates
+}
+
+struct CommandCompleter {
+    names: Vec<&'static str>,
+}
+
+impl Completer for CommandCompleter {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &rustyline::Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok((0, complete(&self.names, &line[..pos])))
+    }
+}
+
+impl Hinter for CommandCompleter {
+    type Hint = String;
+}
+impl Highlighter for CommandCompleter {}
+impl Validator for CommandCompleter {}
+impl Helper for CommandCompleter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repl(day: u32) -> Repl<'static> {
+        let solution = super::super::get_solution(day).unwrap();
+        let source = super::super::input_path(super::super::DEFAULT_INPUT_DIR, day, true);
+        Repl::load(solution, source, RunContext::new(true)).unwrap()
+    }
+
+    fn eval(repl: &mut Repl<'_>, line: &str) -> Result<String> {
+        match repl.eval(line)? {
+            Step::Print(out) => Ok(out),
+            Step::Quit => Ok("quit".to_string()),
+        }
+    }
+
+    #[test]
+    fn day_and_builtin_commands() {
+        let mut repl = repl(5);
+        assert!(eval(&mut repl, "solve 1").unwrap().starts_with("CMZ\n"));
+        let tower = eval(&mut repl, "moves 1").unwrap();
+        let rows = tower.lines().collect::<Vec<_>>();
+        assert_eq!(rows[0].trim_end(), "[D]");
+        assert_eq!(rows[2].trim_end(), "[Z] [M] [P]");
+        assert_eq!(rows[3].trim_end(), " 1   2   3   4   5   6   7   8   9");
+        assert!(eval(&mut repl, "moves x").is_err());
+
+        assert!(eval(&mut repl, "set tower_height=2").is_err());
+        assert_eq!(
+            eval(&mut repl, "set tower_width = 10").unwrap(),
+            "tower_width = 10"
+        );
+        assert!(eval(&mut repl, "solve 1").unwrap().starts_with("CMZ\n"));
+        eval(&mut repl, "set tower_width=2").unwrap();
+        assert!(eval(&mut repl, "solve 1").is_err());
+        assert!(eval(&mut repl, "moves 1").is_err());
+
+        assert!(eval(&mut repl, "fly").is_err());
+        assert!(eval(&mut repl, "help")
+            .unwrap()
+            .contains("moves <count> [9001]"));
+        assert_eq!(eval(&mut repl, "  ").unwrap(), "");
+        assert_eq!(eval(&mut repl, "quit").unwrap(), "quit");
+    }
+
+    #[test]
+    fn complete_command_names() {
+        let names = repl(15)
+            .commands()
+            .iter()
+            .map(|c| c.name)
+            .collect::<Vec<_>>();
+        assert_eq!(complete(&names, "r"), vec!["reload", "row"]);
+        assert_eq!(complete(&names, "row 1"), Vec::<String>::new());
+        assert_eq!(complete(&names, "").len(), names.len());
+    }
+}
@@ -0,0 +1,273 @@
+use std::{path, time};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::{
+    answers::{AnswerStore, Verdict},
+    Answer, ParseError, Parsed, RunContext, Solution,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+impl OutputFormat {
+    pub fn from_args(args: &clap::ArgMatches) -> Result<OutputFormat> {
+        match args.value_of("format").unwrap_or("text") {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            f => Err(anyhow::anyhow!("unknown output format: {:?}", f)),
+        }
+    }
+}
+
+/// One day's input after parsing, shared by both parts
+pub struct ParsedInput {
+    /// `None` when the input was read from stdin
+    pub path: Option<path::PathBuf>,
+    pub parsed: Result<Parsed>,
+    /// SHA-256 of the input text, `None` when it could not be read
+    pub digest: Option<String>,
+    pub read: time::Duration,
+    pub parse: time::Duration,
+}
+
+impl ParsedInput {
+    pub fn parse(
+        s: &dyn Solution,
+        path: Option<&path::Path>,
+        input: &str,
+        read: time::Duration,
+    ) -> ParsedInput {
+        let _span =
+            tracing::info_span!("parse", day = s.day(), input_bytes = input.len()).entered();
+        log::debug!("parsing day {}", s.day());
+        let start = time::Instant::now();
+        let parsed = s.parse(input);
+        let parse = start.elapsed();
+        drop(_span);
+        ParsedInput {
+            path: path.map(|p| p.to_path_buf()),
+            parsed,
+            digest: Some(super::history::digest(input)),
+            read,
+            parse,
+        }
+    }
+
+    /// An input that could not be read at all
+    pub fn failed(path: Option<&path::Path>, error: anyhow::Error, read: time::Duration) -> Self {
+        ParsedInput {
+            path: path.map(|p| p.to_path_buf()),
+            parsed: Err(error),
+            digest: None,
+            read,
+            parse: time::Duration::ZERO,
+        }
+    }
+}
+
+/// The result of running a single day/part against one input
+pub struct Outcome {
+    pub day: u32,
+    pub part: u32,
+    /// `None` when the input was read from stdin
+    pub input: Option<path::PathBuf>,
+    pub digest: Option<String>,
+    pub answer: Result<Answer>,
+    pub read: time::Duration,
+    pub parse: time::Duration,
+    pub solve: time::Duration,
+    pub verdict: Option<Verdict>,
+}
+
+/// A copy of `e` for each part that shares a failed parse. Every cause in the
+/// chain is kept, and a located root cause stays a `ParseError` so it can
+/// still be rendered.
+fn copy_error(e: &anyhow::Error) -> anyhow::Error {
+    let mut causes = e.chain().rev();
+    let root = causes.next().expect("an error chain is never empty");
+    let copy = match root.downcast_ref::<ParseError>() {
+        Some(located) => located.clone().into(),
+        None => anyhow::anyhow!("{}", root),
+    };
+    causes.fold(copy, |copy, cause| copy.context(cause.to_string()))
+}
+
+impl Outcome {
+    pub fn solve(s: &dyn Solution, part: u32, input: &ParsedInput, ctx: &RunContext) -> Outcome {
+        let _span = tracing::info_span!("solve", day = s.day(), part).entered();
+        log::debug!("running day {}:{}", s.day(), part);
+        let start = time::Instant::now();
+        let answer = match &input.parsed {
+            Ok(parsed) => s.solve_parsed(part, parsed, ctx),
+            Err(e) => Err(copy_error(e)),
+        };
+        Outcome {
+            day: s.day(),
+            part,
+            input: input.path.clone(),
+            digest: input.digest.clone(),
+            answer,
+            read: input.read,
+            parse: input.parse,
+            solve: start.elapsed(),
+            verdict: None,
+        }
+    }
+
+    pub fn check(&mut self, answers: &AnswerStore) {
+        if let Ok(answer) = &self.answer {
+            let answer = answer.to_string();
+            self.verdict = Some(answers.check(self.day, self.part, self.input.as_deref(), &answer));
+        }
+    }
+
+    pub fn failed(&self) -> bool {
+        self.answer.is_err() || matches!(self.verdict, Some(Verdict::Fail { .. }))
+    }
+
+    /// Surface a failed solve or a failed answer check as an error
+    pub fn into_result(self) -> Result<()> {
+        self.answer?;
+        if let Some(Verdict::Fail { expected }) = self.verdict {
+            anyhow::bail!("expected answer: {}", expected.trim_end())
+        }
+        Ok(())
+    }
+
+    pub fn record(&self) -> Record<'_> {
+        let (answer, error) = match &self.answer {
+            Ok(a) => (Some(a), Vec::new()),
+            Err(e) => (None, e.chain().map(|cause| cause.to_string()).collect()),
+        };
+        Record {
+            day: self.day,
+            part: self.part,
+            input: self.input.as_ref().map(|p| p.display().to_string()),
+            answer,
+            read_secs: self.read.as_secs_f64(),
+            parse_secs: self.parse.as_secs_f64(),
+            solve_secs: self.solve.as_secs_f64(),
+            check: self.verdict.as_ref().map(|v| v.to_string()),
+            error,
+        }
+    }
+}
+
+/// Machine-readable form of an `Outcome`, emitted by `--format json`
+#[derive(Debug, Serialize)]
+pub struct Record<'a> {
+    pub day: u32,
+    pub part: u32,
+    pub input: Option<String>,
+    pub answer: Option<&'a Answer>,
+    pub read_secs: f64,
+    pub parse_secs: f64,
+    pub solve_secs: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub check: Option<String>,
+    pub error: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(answer: Result<Answer>) -> Outcome {
+        Outcome {
+            day: 10,
+            part: 2,
+            input: Some(path::PathBuf::from("input/day10")),
+            digest: None,
+            answer,
+            read: time::Duration::from_millis(1),
+            parse: time::Duration::from_millis(1),
+            solve: time::Duration::from_millis(2),
+            verdict: None,
+        }
+    }
+
+    #[test]
+    fn json_typed_answers() {
+        let json = serde_json::to_value(outcome(Ok(Answer::Int(13140))).record()).unwrap();
+        assert_eq!(json["answer"], 13140);
+        let json = serde_json::to_value(outcome(Ok(Answer::Text("CMZ".into()))).record()).unwrap();
+        assert_eq!(json["answer"], "CMZ");
+    }
+
+    #[test]
+    fn json_grid_answer() {
+        let o = outcome(Ok(Answer::Grid(vec!["#.".into(), ".#".into()])));
+        assert_eq!(o.answer.as_ref().unwrap().to_string(), "#.\n.#\n");
+        let json = serde_json::to_value(o.record()).unwrap();
+        assert_eq!(json["answer"], serde_json::json!(["#.", ".#"]));
+        assert_eq!(json["input"], "input/day10");
+        assert_eq!(json["solve_secs"], 0.002);
+        assert!(json.get("check").is_none());
+    }
+
+    #[test]
+    fn json_error_chain() {
+        let err = anyhow::anyhow!("root cause").context("could not parse");
+        let o = outcome(Err(err));
+        let json = serde_json::to_value(o.record()).unwrap();
+        assert!(json["answer"].is_null());
+        assert_eq!(
+            json["error"],
+            serde_json::json!(["could not parse", "root cause"])
+        );
+    }
+
+    #[test]
+    fn parse_error_shared_by_parts() {
+        let day = crate::challenge::get_solution(1).unwrap();
+        let input = ParsedInput::parse(day, None, "1000\n\n3000\nlots\n", time::Duration::ZERO);
+        for part in [1, 2] {
+            let o = Outcome::solve(day, part, &input, &RunContext::default());
+            let json = serde_json::to_value(o.record()).unwrap();
+            assert_eq!(json["error"][0], "record 2 (starting on line 3)");
+            assert_eq!(json["error"].as_array().unwrap().len(), 2);
+            let e = o.answer.unwrap_err();
+            assert_eq!(e.downcast_ref::<ParseError>().unwrap().line, 4);
+        }
+    }
+
+    #[test]
+    fn chrome_trace_spans() {
+        use tracing_subscriber::prelude::*;
+
+        let path = std::env::temp_dir().join(format!("aoc-trace-{}.json", std::process::id()));
+        let (layer, guard) = tracing_chrome::ChromeLayerBuilder::new()
+            .file(&path)
+            .include_args(true)
+            .build();
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            let day = crate::challenge::get_solution(10).unwrap();
+            let input = include_str!("../../input/day10_ex");
+            let parsed = ParsedInput::parse(day, None, input, time::Duration::ZERO);
+            Outcome::solve(day, 1, &parsed, &RunContext::default());
+        });
+        drop(guard);
+
+        let trace: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let spans = trace
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|e| e["ph"] == "B")
+            .map(|e| (e["name"].as_str().unwrap(), e["args"].clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(spans.len(), 2, "{}", trace);
+        assert_eq!(spans[0].0, "parse");
+        assert_eq!(spans[0].1["day"], "10");
+        assert_eq!(spans[1].0, "solve");
+        assert_eq!(spans[1].1["part"], "1");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
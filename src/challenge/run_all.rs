@@ -0,0 +1,209 @@
+use std::{path, time};
+
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+
+use super::{
+    answers::AnswerStore,
+    report::{Outcome, OutputFormat, ParsedInput},
+    RunContext, Solution,
+};
+
+pub fn run_all(args: &clap::ArgMatches) -> Result<()> {
+    let input_dir = path::Path::new(args.value_of("input_dir").unwrap());
+    let example = args.is_present("example");
+    let format = OutputFormat::from_args(args)?;
+    let answers = if args.is_present("check") {
+        Some(AnswerStore::load(args.value_of("answers").unwrap())?)
+    } else {
+        None
+    };
+    let jobs = args.value_of("jobs").unwrap().parse::<usize>()?;
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .context("could not start the worker pool")?;
+
+    let start = time::Instant::now();
+    let outcomes = pool.install(|| {
+        run_days(
+            super::solutions(),
+            input_dir,
+            &RunContext::new(example),
+            answers.as_ref(),
+        )
+    });
+    let wall = start.elapsed();
+
+    match format {
+        OutputFormat::Text => print_table(&outcomes, wall),
+        OutputFormat::Json => {
+            let records = outcomes.iter().map(|o| o.record()).collect::<Vec<_>>();
+            println!("{}", serde_json::to_string_pretty(&records)?);
+        }
+    }
+
+    if !args.is_present("no_record") {
+        super::history::record(args.value_of("runs").unwrap(), &outcomes);
+    }
+
+    let failures = outcomes.iter().filter(|o| o.failed()).count();
+    if failures > 0 {
+        anyhow::bail!("{} of {} puzzles failed", failures, outcomes.len())
+    }
+    Ok(())
+}
+
+/// Read, parse and solve every day on the current rayon pool.
+///
+/// Each day is parsed once, then both parts are solved concurrently. The
+/// outcomes come back in registry order, regardless of which job finished first.
+fn run_days(
+    solutions: &[&dyn Solution],
+    input_dir: &path::Path,
+    ctx: &RunContext,
+    answers: Option<&AnswerStore>,
+) -> Vec<Outcome> {
+    solutions
+        .par_iter()
+        .flat_map_iter(|s| {
+            let input_path = super::input_path(input_dir, s.day(), ctx.example);
+            let start = time::Instant::now();
+            let input = super::read_to_string(&input_path);
+            let read = start.elapsed();
+            let parsed = match input {
+                Ok(input) => ParsedInput::parse(*s, Some(&input_path), &input, read),
+                Err(e) => ParsedInput::failed(Some(&input_path), e, read),
+            };
+            let (p1, p2) = rayon::join(
+                || Outcome::solve(*s, 1, &parsed, ctx),
+                || Outcome::solve(*s, 2, &parsed, ctx),
+            );
+            let mut outcomes = [p1, p2];
+            if let Some(answers) = answers {
+                outcomes.iter_mut().for_each(|o| o.check(answers));
+            }
+            outcomes
+        })
+        .collect()
+}
+
+fn fmt_duration(d: time::Duration) -> String {
+    format!("{:.2?}", d)
+}
+
+fn print_table(outcomes: &[Outcome], wall: time::Duration) {
+    let answers = outcomes
+        .iter()
+        .map(|o| match &o.answer {
+            Ok(a) => a.to_string().trim_end().to_string(),
+            Err(e) => format!("error: {:#}", e),
+        })
+        .collect::<Vec<_>>();
+
+    let answer_width = answers
+        .iter()
+        .flat_map(|a| a.lines())
+        .map(|l| l.chars().count())
+        .chain(std::iter::once("answer".len()))
+        .max()
+        .unwrap_or(0);
+
+    let check_header = if outcomes.iter().any(|o| o.verdict.is_some()) {
+        "check"
+    } else {
+        ""
+    };
+
+    let header = format!(
+        "{:>3}  {:>4}  {:<w$}  {:>10}  {:>10}  {:>10}  {}",
+        "day",
+        "part",
+        "answer",
+        "read",
+        "parse",
+        "solve",
+        check_header,
+        w = answer_width
+    );
+    println!("{}", header.trim_end());
+
+    for (outcome, answer) in outcomes.iter().zip(answers.iter()) {
+        let mut lines = answer.lines();
+        let verdict = outcome
+            .verdict
+            .as_ref()
+            .map(|v| v.to_string())
+            .unwrap_or_default();
+        let line = format!(
+            "{:>3}  {:>4}  {:<w$}  {:>10}  {:>10}  {:>10}  {}",
+            outcome.day,
+            outcome.part,
+            lines.next().unwrap_or(""),
+            fmt_duration(outcome.read),
+            fmt_duration(outcome.parse),
+            fmt_duration(outcome.solve),
+            verdict,
+            w = answer_width
+        );
+        println!("{}", line.trim_end());
+        for l in lines {
+            println!("{:>3}  {:>4}  {}", "", "", l);
+        }
+    }
+
+    // each parsed input is shared by both of its parts, so count it once
+    let total = outcomes
+        .iter()
+        .map(|o| {
+            o.solve
+                + if o.part == 1 {
+                    o.read + o.parse
+                } else {
+                    time::Duration::ZERO
+                }
+        })
+        .sum::<time::Duration>();
+    for (label, duration) in [("total", total), ("wall", wall)] {
+        println!(
+            "{:>3}  {:>4}  {:<w$}  {:>10}  {:>10}  {:>10}",
+            "",
+            "",
+            label,
+            "",
+            "",
+            fmt_duration(duration),
+            w = answer_width
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outcomes_are_ordered() {
+        let answers = AnswerStore::load("answers.toml").unwrap();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        let ctx = RunContext::new(true);
+        let outcomes = pool.install(|| {
+            run_days(
+                super::super::solutions(),
+                path::Path::new(super::super::DEFAULT_INPUT_DIR),
+                &ctx,
+                Some(&answers),
+            )
+        });
+        let order = outcomes.iter().map(|o| (o.day, o.part)).collect::<Vec<_>>();
+        let expected = super::super::solutions()
+            .iter()
+            .flat_map(|s| [(s.day(), 1), (s.day(), 2)])
+            .collect::<Vec<_>>();
+        assert_eq!(order, expected);
+        assert!(outcomes.iter().all(|o| !o.failed()));
+    }
+}
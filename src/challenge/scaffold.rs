@@ -0,0 +1,134 @@
+use std::{fs, path};
+
+use anyhow::{Context, Result};
+
+const TEMPLATE: &str = include_str!("template/mod.rs");
+const TEMPLATE_DAY: &str = "    fn day(&self) -> u32 {\n        0\n    }";
+
+pub fn run_new_day(args: &clap::ArgMatches) -> Result<()> {
+    let day = args.value_of("day").unwrap().parse::<u32>()?;
+    let root = path::Path::new(args.value_of("root").unwrap());
+    new_day(root, day)
+}
+
+/// Create `src/challenge/day{N}` from the template, empty inputs for it,
+/// and register it in `challenge/mod.rs`
+pub fn new_day(root: &path::Path, day: u32) -> Result<()> {
+    if day == 0 {
+        anyhow::bail!("days start at 1")
+    }
+    let challenge_dir = root.join("src").join("challenge");
+    let registry_path = challenge_dir.join("mod.rs");
+    let day_dir = challenge_dir.join(format!("day{}", day));
+    if day_dir.exists() {
+        anyhow::bail!("{} already exists", day_dir.display())
+    }
+
+    let registry = super::read_to_string(&registry_path)?;
+    let registry = register_day(&registry, day)?;
+
+    fs::create_dir_all(&day_dir)
+        .with_context(|| format!("Unable to create directory: {}", day_dir.display()))?;
+    write(&day_dir.join("mod.rs"), &instantiate_template(day)?)?;
+
+    let input_dir = root.join(super::DEFAULT_INPUT_DIR);
+    fs::create_dir_all(&input_dir)
+        .with_context(|| format!("Unable to create directory: {}", input_dir.display()))?;
+    for example in [false, true] {
+        let input = super::input_path(&input_dir, day, example);
+        if !input.exists() {
+            write(&input, "")?;
+        }
+    }
+
+    write(&registry_path, &registry)?;
+    println!("created {}", day_dir.display());
+    Ok(())
+}
+
+fn write(path: &path::Path, content: &str) -> Result<()> {
+    fs::write(path, content).with_context(|| format!("Unable to write: {}", path.display()))
+}
+
+fn instantiate_template(day: u32) -> Result<String> {
+    if !TEMPLATE.contains(TEMPLATE_DAY) {
+        anyhow::bail!("template no longer contains `fn day` returning 0")
+    }
+    Ok(TEMPLATE
+        .replace(
+            TEMPLATE_DAY,
+            &TEMPLATE_DAY.replace(" 0\n", &format!(" {}\n", day)),
+        )
+        .replace("\"Template\"", &format!("\"Day {}\"", day))
+        .replace("Day0", &format!("Day{}", day))
+        .replace("day0", &format!("day{}", day)))
+}
+
+/// Add `pub mod day{N};` and `&day{N}::Day{N},` to the registry source, keeping days in order
+fn register_day(registry: &str, day: u32) -> Result<String> {
+    let module = format!("pub mod day{};", day);
+    let entry = format!("    &day{}::Day{},", day, day);
+    let module_day = |l: &str| {
+        l.strip_prefix("pub mod day")?
+            .strip_suffix(';')?
+            .parse()
+            .ok()
+    };
+    let entry_day = |l: &str| l.strip_prefix("    &day")?.split_once("::")?.0.parse().ok();
+
+    let mut lines = registry.lines().map(str::to_string).collect::<Vec<_>>();
+    insert_in_order(&mut lines, module, day, module_day)?;
+    insert_in_order(&mut lines, entry, day, entry_day)?;
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    Ok(out)
+}
+
+/// Insert `line` after the last line belonging to an earlier day
+fn insert_in_order<F>(lines: &mut Vec<String>, line: String, day: u32, day_of: F) -> Result<()>
+where
+    F: Fn(&str) -> Option<u32>,
+{
+    let days = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, l)| day_of(l).map(|d| (idx, d)))
+        .collect::<Vec<_>>();
+    if days.iter().any(|&(_, d)| d == day) {
+        anyhow::bail!("day {} is already registered", day)
+    }
+    let idx = match days.iter().rfind(|&&(_, d)| d < day) {
+        Some(&(idx, _)) => idx + 1,
+        None => days
+            .first()
+            .map(|&(idx, _)| idx)
+            .ok_or_else(|| anyhow::anyhow!("could not find where to register {:?}", line))?,
+    };
+    lines.insert(idx, line);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_is_renamed() {
+        let src = instantiate_template(16).unwrap();
+        assert!(src.contains("pub struct Day16;"));
+        assert!(src.contains("impl Day for Day16 {"));
+        assert!(src.contains("    fn day(&self) -> u32 {\n        16\n    }"));
+        assert!(src.contains("include_str!(\"../../../input/day16_ex\")"));
+        assert!(!src.contains("Day0") && !src.contains("day0"));
+    }
+
+    #[test]
+    fn register_in_order() {
+        let registry = include_str!("mod.rs");
+        let updated = register_day(registry, 16).unwrap();
+        assert!(updated.contains("pub mod day15;\npub mod day16;\n"));
+        assert!(updated.contains("    &day15::Day15,\n    &day16::Day16,\n"));
+        assert!(register_day(registry, 15).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+use std::any::Any;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::{Command, CommandHelp, Param, RunContext};
+
+/// A solved puzzle for a single day, with a typed model of the parsed input.
+///
+/// Every `Day` is also a `Solution`, which is what gets registered in `challenge::solutions`
+pub trait Day: Sync {
+    type Input: Any + Send + Sync;
+
+    /// The puzzle title, as it appears on the website
+    fn name(&self) -> &'static str;
+    fn day(&self) -> u32;
+
+    fn parse(&self, input: &str) -> Result<Self::Input>;
+    fn solve_p1(&self, input: &Self::Input, ctx: &RunContext) -> Result<Answer>;
+    fn solve_p2(&self, input: &Self::Input, ctx: &RunContext) -> Result<Answer>;
+
+    /// Example inputs from the puzzle text, along with the answers they should produce
+    fn examples(&self) -> &'static [Example] {
+        &[]
+    }
+
+    /// Puzzle parameters this day reads from the `RunContext`, which can be overridden with `--param`
+    fn params(&self) -> &'static [Param] {
+        &[]
+    }
+
+    /// Commands for exploring the parsed input from `repl`
+    fn commands(&self) -> &'static [Command<Self::Input>] {
+        &[]
+    }
+
+    /// A random input with about `size` records, in the format `parse` accepts
+    fn generate(&self, _rng: &mut fastrand::Rng, _size: usize) -> Option<String> {
+        None
+    }
+}
+
+/// A parsed puzzle input, as produced by `Solution::parse`
+pub type Parsed = Box<dyn Any + Send + Sync>;
+
+/// Type-erased view of a `Day`, so that every day can be driven by the same runner
+pub trait Solution: Sync {
+    fn name(&self) -> &'static str;
+    fn day(&self) -> u32;
+    fn examples(&self) -> &'static [Example];
+    fn params(&self) -> &'static [Param];
+    fn commands(&self) -> Vec<CommandHelp>;
+
+    fn parse(&self, input: &str) -> Result<Parsed>;
+
+    /// Solve one part using input previously returned by `parse` for this day
+    fn solve_parsed(&self, part: u32, parsed: &Parsed, ctx: &RunContext) -> Result<Answer>;
+
+    /// Run the day's `repl` command `name`, or `None` when the day has no such command
+    fn run_command(
+        &self,
+        parsed: &Parsed,
+        name: &str,
+        args: &[&str],
+        ctx: &RunContext,
+    ) -> Option<Result<String>>;
+
+    /// A random puzzle input, or `None` when the day has no generator
+    fn generate(&self, rng: &mut fastrand::Rng, size: usize) -> Option<String>;
+
+    fn solve(&self, part: u32, input: &str, ctx: &RunContext) -> Result<Answer> {
+        let parsed = self.parse(input)?;
+        self.solve_parsed(part, &parsed, ctx)
+    }
+
+    /// Solve part 1 of a real (non-example) input with default parameters
+    fn part1(&self, input: &str) -> Result<Answer> {
+        self.solve(1, input, &RunContext::default())
+    }
+
+    /// Solve part 2 of a real (non-example) input with default parameters
+    fn part2(&self, input: &str) -> Result<Answer> {
+        self.solve(2, input, &RunContext::default())
+    }
+}
+
+impl<D: Day> Solution for D {
+    fn name(&self) -> &'static str {
+        Day::name(self)
+    }
+
+    fn day(&self) -> u32 {
+        Day::day(self)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        Day::examples(self)
+    }
+
+    fn params(&self) -> &'static [Param] {
+        Day::params(self)
+    }
+
+    fn commands(&self) -> Vec<CommandHelp> {
+        Day::commands(self).iter().map(|c| c.describe()).collect()
+    }
+
+    fn parse(&self, input: &str) -> Result<Parsed> {
+        Ok(Box::new(Day::parse(self, input)?))
+    }
+
+    fn solve_parsed(&self, part: u32, parsed: &Parsed, ctx: &RunContext) -> Result<Answer> {
+        let input = parsed.downcast_ref::<D::Input>().ok_or_else(|| {
+            anyhow::anyhow!("parsed input does not belong to day {}", Day::day(self))
+        })?;
+        match part {
+            1 => self.solve_p1(input, ctx),
+            2 => self.solve_p2(input, ctx),
+            p => anyhow::bail!("unimplemented challenge day {} part {}", Day::day(self), p),
+        }
+    }
+
+    fn run_command(
+        &self,
+        parsed: &Parsed,
+        name: &str,
+        args: &[&str],
+        ctx: &RunContext,
+    ) -> Option<Result<String>> {
+        let command = Day::commands(self).iter().find(|c| c.name == name)?;
+        Some(
+            parsed
+                .downcast_ref::<D::Input>()
+                .ok_or_else(|| {
+                    anyhow::anyhow!("parsed input does not belong to day {}", Day::day(self))
+                })
+                .and_then(|input| (command.run)(input, args, ctx)),
+        )
+    }
+
+    fn generate(&self, rng: &mut fastrand::Rng, size: usize) -> Option<String> {
+        Day::generate(self, rng, size)
+    }
+}
+
+/// A puzzle answer, keeping track of what kind of value the puzzle asked for
+///
+/// `Display` gives the form that is entered on the website; JSON keeps the type,
+/// so integers serialize as numbers and grids as an array of rows.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+    /// Rows of a rendered picture, e.g. the day 10 CRT screen
+    Grid(Vec<String>),
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(x) => write!(f, "{}", x),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Grid(rows) => rows.iter().try_for_each(|row| writeln!(f, "{}", row)),
+        }
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(x: i64) -> Self {
+        Answer::Int(x)
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(x: i32) -> Self {
+        Answer::Int(x.into())
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(x: usize) -> Self {
+        Answer::Int(x as i64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+/// Expected answers for each part of an example input
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Expected {
+    pub part1: Option<&'static str>,
+    pub part2: Option<&'static str>,
+}
+
+impl Expected {
+    pub fn part(&self, part: u32) -> Option<&'static str> {
+        match part {
+            1 => self.part1,
+            2 => self.part2,
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Example {
+    pub input: &'static str,
+    pub expected: Expected,
+}
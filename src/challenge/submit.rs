@@ -0,0 +1,322 @@
+use std::time;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    fetch::{USER_AGENT, YEAR},
+    jsonl::JsonLines,
+};
+
+pub fn run_submit(args: &clap::ArgMatches) -> Result<()> {
+    let day = args.value_of("day").unwrap().parse::<u32>()?;
+    let part = args.value_of("part").unwrap().parse::<u32>()?;
+    let solution = super::get_solution(day)
+        .ok_or_else(|| anyhow::anyhow!("unimplemented challenge day {} part {}", day, part))?;
+
+    let answer = match args.value_of("answer") {
+        Some(answer) => answer.to_string(),
+        None => {
+            let source = super::input_source(args.value_of("input"), day, false);
+            let ctx = super::run_context(solution, source.as_deref(), args.values_of("param"))?;
+            if ctx.example {
+                anyhow::bail!("refusing to submit the answer to an example input")
+            }
+            let input = super::read_input(source.as_deref())?;
+            solution.solve(part, &input, &ctx)?.to_string()
+        }
+    };
+
+    let client = SubmitClient {
+        base_url: args
+            .value_of("base_url")
+            .unwrap()
+            .trim_end_matches('/')
+            .to_string(),
+        session: args
+            .value_of("session")
+            .ok_or_else(|| anyhow::anyhow!("a session token is required to submit"))?
+            .trim()
+            .to_string(),
+        history: History::new(args.value_of("history").unwrap()),
+    };
+    let reply = client.submit(day, part, answer.trim_end())?;
+    println!("{}", reply);
+    match reply {
+        Reply::Correct | Reply::AlreadySolved => Ok(()),
+        _ => anyhow::bail!("day {} part {} was not accepted", day, part),
+    }
+}
+
+/// What the server made of a submitted answer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reply {
+    Correct,
+    /// Wrong, and the correct answer is lower
+    TooHigh,
+    /// Wrong, and the correct answer is higher
+    TooLow,
+    Wrong,
+    /// Submitted too soon after a previous attempt
+    Wait {
+        seconds: u64,
+    },
+    /// The part was already solved, or is not unlocked yet
+    AlreadySolved,
+    Unrecognized,
+}
+
+impl std::fmt::Display for Reply {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reply::Correct => write!(f, "correct"),
+            Reply::TooHigh => write!(f, "wrong: too high"),
+            Reply::TooLow => write!(f, "wrong: too low"),
+            Reply::Wrong => write!(f, "wrong"),
+            Reply::Wait { seconds } => write!(f, "rate limited: wait {}s", seconds),
+            Reply::AlreadySolved => write!(f, "already solved (or not unlocked yet)"),
+            Reply::Unrecognized => write!(f, "unrecognized response"),
+        }
+    }
+}
+
+impl Reply {
+    /// Classify the article text of the answer page
+    pub fn parse(page: &str) -> Reply {
+        if page.contains("That's the right answer") {
+            Reply::Correct
+        } else if page.contains("your answer is too high") {
+            Reply::TooHigh
+        } else if page.contains("your answer is too low") {
+            Reply::TooLow
+        } else if page.contains("That's not the right answer") {
+            Reply::Wrong
+        } else if page.contains("You gave an answer too recently") {
+            Reply::Wait {
+                seconds: parse_wait(page).unwrap_or(60),
+            }
+        } else if page.contains("You don't seem to be solving the right level") {
+            Reply::AlreadySolved
+        } else {
+            Reply::Unrecognized
+        }
+    }
+
+    fn is_wrong(&self) -> bool {
+        matches!(self, Reply::TooHigh | Reply::TooLow | Reply::Wrong)
+    }
+}
+
+/// Seconds from "you have 1m 5s left to wait"
+fn parse_wait(page: &str) -> Option<u64> {
+    let (before, _) = page.split_once(" left to wait")?;
+    let (_, wait) = before.rsplit_once("have ")?;
+    wait.split_whitespace()
+        .map(|w| {
+            let (value, unit) = w.split_at(w.find(|c: char| !c.is_ascii_digit())?);
+            let value = value.parse::<u64>().ok()?;
+            match unit {
+                "h" => Some(value * 3600),
+                "m" => Some(value * 60),
+                "s" => Some(value),
+                _ => None,
+            }
+        })
+        .sum()
+}
+
+/// One submitted answer, as stored in the history file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attempt {
+    pub day: u32,
+    pub part: u32,
+    pub answer: String,
+    pub reply: Reply,
+    /// Seconds since the unix epoch
+    pub at: u64,
+}
+
+/// Every submission ever made
+pub type History = JsonLines<Attempt>;
+
+/// Reasons not to send `answer`, judging by earlier attempts at the same part
+pub fn check_attempt(
+    history: &[Attempt],
+    day: u32,
+    part: u32,
+    answer: &str,
+    now: u64,
+) -> Result<()> {
+    let attempts = history.iter().filter(|a| a.day == day && a.part == part);
+    let value = answer.parse::<i64>().ok();
+    for attempt in attempts {
+        match &attempt.reply {
+            Reply::Correct => anyhow::bail!(
+                "day {} part {} was already solved with {}",
+                day,
+                part,
+                attempt.answer
+            ),
+            Reply::Wait { seconds } if attempt.at + seconds > now => {
+                anyhow::bail!("rate limited, wait another {}s", attempt.at + seconds - now)
+            }
+            r if r.is_wrong() && attempt.answer == answer => {
+                anyhow::bail!("{} was already rejected ({})", answer, r)
+            }
+            _ => {}
+        }
+        let bound = attempt.answer.parse::<i64>().ok();
+        match (&attempt.reply, value, bound) {
+            (Reply::TooHigh, Some(v), Some(b)) if v >= b => {
+                anyhow::bail!("{} is not below {}, which was too high", v, b)
+            }
+            (Reply::TooLow, Some(v), Some(b)) if v <= b => {
+                anyhow::bail!("{} is not above {}, which was too low", v, b)
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+pub struct SubmitClient {
+    pub base_url: String,
+    pub session: String,
+    pub history: History,
+}
+
+impl SubmitClient {
+    /// Post an answer unless the history says it can not be right, and record the reply
+    pub fn submit(&self, day: u32, part: u32, answer: &str) -> Result<Reply> {
+        if answer.is_empty() || answer.contains('\n') {
+            anyhow::bail!("answers are submitted as a single line, got {:?}", answer)
+        }
+        check_attempt(&self.history.load()?, day, part, answer, now())?;
+
+        let url = format!("{}/{}/day/{}/answer", self.base_url, YEAR, day);
+        log::info!("submitting {:?} to {}", answer, url);
+        let level = part.to_string();
+        let page = match ureq::post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .send_form(&[("level", &level), ("answer", answer)])
+        {
+            Ok(r) => r.into_string()?,
+            Err(ureq::Error::Status(code, _)) => anyhow::bail!("{} returned {}", url, code),
+            Err(e) => return Err(e.into()),
+        };
+
+        let reply = Reply::parse(&page);
+        if reply == Reply::Unrecognized {
+            log::warn!("could not understand the response:\n{}", page);
+        }
+        self.history.append(&[Attempt {
+            day,
+            part,
+            answer: answer.to_string(),
+            reply: reply.clone(),
+            at: now(),
+        }])?;
+        Ok(reply)
+    }
+}
+
+fn now() -> u64 {
+    time::SystemTime::now()
+        .duration_since(time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::challenge::test_server::serve;
+    use std::fs;
+
+    const CORRECT: &str = "<article><p>That's the right answer! You are one gold star closer to saving your vacation.</p></article>";
+    const TOO_HIGH: &str = "<article><p>That's not the right answer; your answer is too high.  If you're stuck, make sure you're using the full input data.</p></article>";
+    const WAIT: &str = "<article><p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait.</p></article>";
+
+    fn attempt(answer: &str, reply: Reply, at: u64) -> Attempt {
+        Attempt {
+            day: 1,
+            part: 1,
+            answer: answer.to_string(),
+            reply,
+            at,
+        }
+    }
+
+    #[test]
+    fn parse_replies() {
+        assert_eq!(Reply::parse(CORRECT), Reply::Correct);
+        assert_eq!(Reply::parse(TOO_HIGH), Reply::TooHigh);
+        assert_eq!(
+            Reply::parse("That's not the right answer; your answer is too low."),
+            Reply::TooLow
+        );
+        assert_eq!(Reply::parse(WAIT), Reply::Wait { seconds: 65 });
+        assert_eq!(
+            Reply::parse(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            ),
+            Reply::AlreadySolved
+        );
+        assert_eq!(Reply::parse("<html></html>"), Reply::Unrecognized);
+    }
+
+    #[test]
+    fn refuse_known_wrong_answers() {
+        let history = vec![
+            attempt("500", Reply::TooHigh, 0),
+            attempt("100", Reply::TooLow, 0),
+            attempt("300", Reply::Wrong, 0),
+        ];
+        assert!(check_attempt(&history, 1, 1, "200", 1000).is_ok());
+        assert!(check_attempt(&history, 1, 1, "300", 1000).is_err());
+        assert!(check_attempt(&history, 1, 1, "500", 1000).is_err());
+        assert!(check_attempt(&history, 1, 1, "600", 1000).is_err());
+        assert!(check_attempt(&history, 1, 1, "50", 1000).is_err());
+        assert!(check_attempt(&history, 1, 2, "600", 1000).is_ok());
+
+        let history = vec![attempt("7", Reply::Wait { seconds: 60 }, 1000)];
+        assert!(check_attempt(&history, 1, 1, "7", 1030).is_err());
+        assert!(check_attempt(&history, 1, 1, "7", 1060).is_ok());
+
+        let history = vec![attempt("7", Reply::Correct, 0)];
+        assert!(check_attempt(&history, 1, 1, "8", 1000).is_err());
+    }
+
+    #[test]
+    fn submit_and_record() {
+        let (base_url, requests) = serve(vec![(200, TOO_HIGH), (200, CORRECT)]);
+        let history =
+            std::env::temp_dir().join(format!("aoc-submissions-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&history);
+        let client = SubmitClient {
+            base_url,
+            session: "abc123".to_string(),
+            history: History::new(&history),
+        };
+
+        assert_eq!(client.submit(1, 2, "208192").unwrap(), Reply::TooHigh);
+        let request = requests.recv().unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.url, "/2022/day/1/answer");
+        assert_eq!(request.cookie, "session=abc123");
+        assert_eq!(request.body, "level=2&answer=208192");
+
+        // refused locally, without reaching the server
+        assert!(client.submit(1, 2, "208193").is_err());
+        assert_eq!(client.submit(1, 2, "208191").unwrap(), Reply::Correct);
+        assert_eq!(requests.recv().unwrap().body, "level=2&answer=208191");
+
+        let attempts = client.history.load().unwrap();
+        assert_eq!(
+            attempts.iter().map(|a| &a.reply).collect::<Vec<_>>(),
+            vec![&Reply::TooHigh, &Reply::Correct]
+        );
+        fs::remove_file(&history).unwrap();
+    }
+}
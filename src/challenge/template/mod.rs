@@ -1,16 +1,47 @@
 use anyhow::{Context, Result};
 
-pub fn part1(input: &str) -> Result<String> {
-    let x = 0;
-    Ok(format!("{:?}", x))
+use super::{Answer, Day, Example, Expected, RunContext};
+
+pub struct Day0;
+
+impl Day for Day0 {
+    type Input = Vec<i64>;
+
+    fn name(&self) -> &'static str {
+        "Template"
+    }
+    fn day(&self) -> u32 {
+        0
+    }
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+    fn solve_p1(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        Ok(solve_p1(input).into())
+    }
+    fn solve_p2(&self, input: &Self::Input, _ctx: &RunContext) -> Result<Answer> {
+        Ok(solve_p2(input).into())
+    }
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            input: include_str!("../../../input/day0_ex"),
+            expected: Expected {
+                part1: None,
+                part2: None,
+            },
+        }]
+    }
+}
+
+pub fn solve_p1(input: &[i64]) -> i64 {
+    0
 }
 
-pub fn part2(input: &str) -> Result<String> {
-    let x = 0;
-    Ok(format!("{:?}", x))
+pub fn solve_p2(input: &[i64]) -> i64 {
+    0
 }
 
-fn parse(input: &str) -> Result<Vec<i64>> {
+pub fn parse(input: &str) -> Result<Vec<i64>> {
     input
         .lines()
         .map(|l| {
@@ -23,14 +54,25 @@ fn parse(input: &str) -> Result<Vec<i64>> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = include_str!("../../../input/day1");
+    use crate::challenge::Solution;
+    const INPUT: &str = include_str!("../../../input/day0");
+    const EX: &str = include_str!("../../../input/day0_ex");
 
     #[test]
     fn verify_p1() {
-        assert_eq!(part1(INPUT).unwrap().as_str(), "0")
+        assert_eq!(Day0.part1(INPUT).unwrap().to_string(), "0")
     }
     #[test]
     fn verify_p2() {
-        assert_eq!(part2(INPUT).unwrap().as_str(), "0")
+        assert_eq!(Day0.part2(INPUT).unwrap().to_string(), "0")
+    }
+
+    #[test]
+    fn p1_ex() {
+        assert_eq!(Day0.part1(EX).unwrap().to_string(), "0")
+    }
+    #[test]
+    fn p2_ex() {
+        assert_eq!(Day0.part2(EX).unwrap().to_string(), "0")
     }
 }
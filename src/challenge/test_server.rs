@@ -0,0 +1,42 @@
+//! A local stand-in for the puzzle website, so the HTTP clients can be tested offline
+
+use std::{sync::mpsc, thread};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub method: String,
+    pub url: String,
+    pub cookie: String,
+    pub body: String,
+}
+
+/// Serve `responses` in order, then shut down. Returns the base url of the
+/// server and a channel receiving every request it answered.
+pub fn serve(responses: Vec<(u16, &'static str)>) -> (String, mpsc::Receiver<Request>) {
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", server.server_addr().to_ip().unwrap());
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for (status, body) in responses {
+            let mut request = server.recv().unwrap();
+            let cookie = request
+                .headers()
+                .iter()
+                .find(|h| h.field.equiv("Cookie"))
+                .map(|h| h.value.to_string())
+                .unwrap_or_default();
+            let mut content = String::new();
+            request.as_reader().read_to_string(&mut content).unwrap();
+            tx.send(Request {
+                method: request.method().to_string(),
+                url: request.url().to_string(),
+                cookie,
+                body: content,
+            })
+            .unwrap();
+            let response = tiny_http::Response::from_string(body).with_status_code(status);
+            request.respond(response).unwrap();
+        }
+    });
+    (base_url, rx)
+}
@@ -0,0 +1,240 @@
+use std::{fs, path, thread, time};
+
+use anyhow::Result;
+
+use super::{
+    answers::AnswerStore,
+    report::{Outcome, ParsedInput},
+    RunContext, Solution,
+};
+
+pub fn run_watch(args: &clap::ArgMatches) -> Result<()> {
+    let day = args.value_of("day").unwrap().parse::<u32>()?;
+    let part = args.value_of("part").unwrap().parse::<u32>()?;
+    let interval = time::Duration::from_millis(args.value_of("interval").unwrap().parse()?);
+    let solution = super::get_solution(day)
+        .ok_or_else(|| anyhow::anyhow!("unimplemented challenge day {} part {}", day, part))?;
+    let input = super::input_source(args.value_of("input"), day, args.is_present("example"))
+        .ok_or_else(|| anyhow::anyhow!("can not watch stdin, give an input file"))?;
+    let ctx = super::run_context(solution, Some(&input), args.values_of("param"))?;
+
+    let mut watch = Watch {
+        solution,
+        part,
+        ctx,
+        input: Watched::new(input),
+        answers: args
+            .value_of("answers")
+            .map(|p| Watched::new(path::PathBuf::from(p))),
+        previous: None,
+    };
+    println!(
+        "watching {} (every {:?}, ctrl-c to stop)",
+        watch.input.path.display(),
+        interval
+    );
+    loop {
+        let input_changed = watch.input.poll();
+        let answers_changed = watch.answers.as_mut().is_some_and(|a| a.poll());
+        if input_changed || answers_changed {
+            println!("{}", watch.run());
+        }
+        thread::sleep(interval);
+    }
+}
+
+/// Last seen state of a file, compared on every poll
+struct Watched {
+    path: path::PathBuf,
+    /// `None` until the first poll
+    stamp: Option<Stamp>,
+}
+
+/// Modification time and length, `None` while the file does not exist
+type Stamp = Option<(time::SystemTime, u64)>;
+
+impl Watched {
+    fn new(path: path::PathBuf) -> Watched {
+        Watched { path, stamp: None }
+    }
+
+    /// Whether the file changed since the last poll. The first poll always counts as a change.
+    fn poll(&mut self) -> bool {
+        let stamp = fs::metadata(&self.path)
+            .and_then(|m| Ok((m.modified()?, m.len())))
+            .ok();
+        self.stamp.replace(stamp) != Some(stamp)
+    }
+}
+
+struct Watch<'a> {
+    solution: &'a dyn Solution,
+    part: u32,
+    ctx: RunContext,
+    input: Watched,
+    answers: Option<Watched>,
+    previous: Option<String>,
+}
+
+impl Watch<'_> {
+    /// Re-read the input, solve it, and describe how the answer moved
+    fn run(&mut self) -> String {
+        let path = self.input.path.as_path();
+        let start = time::Instant::now();
+        let (parsed, expected) = match super::read_input(Some(path)) {
+            Ok(input) => (
+                ParsedInput::parse(self.solution, Some(path), &input, start.elapsed()),
+                self.expected(&input),
+            ),
+            Err(e) => (ParsedInput::failed(Some(path), e, start.elapsed()), None),
+        };
+        let outcome = Outcome::solve(self.solution, self.part, &parsed, &self.ctx);
+        let answer = outcome.answer.as_ref().map(|a| a.to_string());
+        let report = describe(
+            &outcome,
+            self.previous.as_deref(),
+            answer.as_deref().map_err(|e| format!("{:#}", e)),
+            expected.as_deref(),
+        );
+        if let Ok(answer) = answer {
+            self.previous = Some(answer);
+        }
+        report
+    }
+
+    /// The example answer when the input is one of the day's examples,
+    /// otherwise whatever the answers file has for this input
+    fn expected(&self, input: &str) -> Option<String> {
+        let example = self
+            .solution
+            .examples()
+            .iter()
+            .find(|ex| ex.input.trim_end() == input.trim_end())
+            .and_then(|ex| ex.expected.part(self.part));
+        if let Some(expected) = example {
+            return Some(expected.to_string());
+        }
+        let answers = self.answers.as_ref()?;
+        match AnswerStore::load(&answers.path) {
+            Ok(store) => store
+                .get(self.solution.day(), self.part, &self.input.path)
+                .map(str::to_string),
+            Err(e) => {
+                log::warn!("{:#}", e);
+                None
+            }
+        }
+    }
+}
+
+fn describe(
+    outcome: &Outcome,
+    previous: Option<&str>,
+    answer: std::result::Result<&str, String>,
+    expected: Option<&str>,
+) -> String {
+    let mut out = format!(
+        "--- day {} part {} (parse {:?}, solve {:?})\n",
+        outcome.day, outcome.part, outcome.parse, outcome.solve
+    );
+    let answer = match answer {
+        Ok(answer) => answer,
+        Err(e) => {
+            out.push_str(&format!("error: {}\n", e));
+            return out;
+        }
+    };
+    out.push_str(answer.trim_end());
+    out.push('\n');
+    match previous {
+        Some(previous) if previous == answer => out.push_str("unchanged since the last run\n"),
+        Some(previous) => {
+            out.push_str("changed since the last run:\n");
+            out.push_str(&diff(previous, answer));
+        }
+        None => {}
+    }
+    match expected {
+        Some(expected) if expected.trim_end() == answer.trim_end() => {
+            out.push_str("matches the expected answer\n")
+        }
+        Some(expected) => {
+            out.push_str("does not match the expected answer:\n");
+            out.push_str(&diff(expected, answer));
+        }
+        None => {}
+    }
+    out
+}
+
+/// Line by line comparison, marking lines only in `old` with `-` and lines only in `new` with `+`
+fn diff(old: &str, new: &str) -> String {
+    let old = old.trim_end().lines().collect::<Vec<_>>();
+    let new = new.trim_end().lines().collect::<Vec<_>>();
+    let mut out = String::new();
+    for idx in 0..old.len().max(new.len()) {
+        match (old.get(idx), new.get(idx)) {
+            (Some(o), Some(n)) if o == n => out.push_str(&format!("  {}\n", o)),
+            (o, n) => {
+                if let Some(o) = o {
+                    out.push_str(&format!("- {}\n", o));
+                }
+                if let Some(n) = n {
+                    out.push_str(&format!("+ {}\n", n));
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_lines() {
+        assert_eq!(diff("12", "13"), "- 12\n+ 13\n");
+        assert_eq!(diff("#.\n..\n", "#.\n.#\n#"), "  #.\n- ..\n+ .#\n+ #\n");
+    }
+
+    #[test]
+    fn reruns_on_change() {
+        let dir = std::env::temp_dir().join(format!("aoc-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("day1_ex");
+        let _ = fs::remove_file(&input);
+
+        let mut watch = Watch {
+            solution: super::super::get_solution(1).unwrap(),
+            part: 1,
+            ctx: RunContext::new(true),
+            input: Watched::new(input.clone()),
+            answers: None,
+            previous: None,
+        };
+        assert!(watch.input.poll());
+        assert!(!watch.input.poll());
+        assert!(watch.run().contains("error: "));
+
+        fs::write(&input, include_str!("../../input/day1_ex")).unwrap();
+        assert!(watch.input.poll());
+        let report = watch.run();
+        assert!(
+            report.contains("\n24000\nmatches the expected answer\n"),
+            "{}",
+            report
+        );
+
+        fs::write(&input, "1000\n\n2000\n").unwrap();
+        assert!(watch.input.poll());
+        let report = watch.run();
+        assert!(
+            report.contains("changed since the last run:\n- 24000\n+ 2000\n"),
+            "{}",
+            report
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,6 @@
+//! Advent of Code 2022 solutions.
+//!
+//! Each `challenge::dayN` module exposes its parser, the model types it parses
+//! into, and the solvers for both parts, so they can be used without the CLI.
+
+pub mod challenge;
@@ -1,14 +1,17 @@
+use advent_of_code_2022::challenge::{self, ParseError};
 use anyhow::Result;
-
-mod challenge;
+use tracing_subscriber::{filter::LevelFilter, prelude::*};
 
 fn main() -> Result<()> {
     color_backtrace::install();
     let args = get_args();
-    setup_logger(args.occurrences_of("verbosity"));
+    let _trace = setup_tracing(args.occurrences_of("verbosity"), args.value_of("trace"))?;
     log::trace!("Args: {:?}", args);
 
     challenge::run(&args).map_err(|e| {
+        if let Some(located) = e.downcast_ref::<ParseError>() {
+            eprintln!("{}\n", located.render());
+        }
         log::error!("{}", e);
         e.chain()
             .skip(1)
@@ -16,44 +19,44 @@ fn main() -> Result<()> {
         anyhow::anyhow!("unrecoverable failure")
     })
 }
-fn setup_logger(level: u64) {
-    let mut builder = pretty_env_logger::formatted_timed_builder();
-
-    let noisy_modules = &[
-        "hyper",
-        "mio",
-        "tokio_core",
-        "tokio_reactor",
-        "tokio_threadpool",
-        "fuse::request",
-        "rusoto_core",
-        "hab_core::dependencies::graph::exec_walker",
-        "want",
-    ];
-
+/// Log to stderr, and when `trace` is given, also record the parse/solve spans
+/// as a Chrome trace (chrome://tracing, Perfetto). The trace is written out when
+/// the returned guard is dropped.
+fn setup_tracing(level: u64, trace: Option<&str>) -> Result<Option<tracing_chrome::FlushGuard>> {
     let log_level = match level {
-        //0 => log::Level::Error,
-        0 => log::LevelFilter::Warn,
-        1 => log::LevelFilter::Info,
-        2 => log::LevelFilter::Debug,
-        _ => log::LevelFilter::Trace,
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
     };
+    let stderr = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_filter(log_level);
 
-    if level > 1 && level < 4 {
-        for module in noisy_modules {
-            builder.filter_module(module, log::LevelFilter::Info);
+    let (chrome, guard) = match trace {
+        Some(path) => {
+            let (layer, guard) = tracing_chrome::ChromeLayerBuilder::new()
+                .file(path)
+                .include_args(true)
+                .build();
+            (Some(layer.with_filter(LevelFilter::INFO)), Some(guard))
         }
-    }
+        None => (None, None),
+    };
 
-    builder.filter_level(log_level);
-    builder.format_timestamp_millis();
-    builder.init();
+    tracing_subscriber::registry()
+        .with(stderr)
+        .with(chrome)
+        .try_init()?;
+    Ok(guard)
 }
 fn get_args() -> clap::ArgMatches<'static> {
     clap::App::new(clap::crate_name!())
         .version(clap::crate_version!())
         .about(clap::crate_description!())
         .setting(clap::AppSettings::DeriveDisplayOrder)
+        .setting(clap::AppSettings::SubcommandsNegateReqs)
+        .setting(clap::AppSettings::ArgsNegateSubcommands)
         .arg(
             clap::Arg::with_name("verbosity")
                 .short("v")
@@ -61,8 +64,287 @@ fn get_args() -> clap::ArgMatches<'static> {
                 .global(true)
                 .help("Sets the level of verbosity"),
         )
+        .arg(
+            clap::Arg::with_name("trace")
+                .long("trace")
+                .takes_value(true)
+                .global(true)
+                .help("Write the parse/solve spans to a Chrome trace JSON file"),
+        )
         .arg(clap::Arg::with_name("day").required(true))
         .arg(clap::Arg::with_name("part").required(true))
-        .arg(clap::Arg::with_name("input").required(true))
+        .args(&input_args())
+        .arg(param_arg())
+        .args(&answer_check_args())
+        .args(&record_args())
+        .arg(format_arg())
+        .subcommand(
+            clap::SubCommand::with_name("run-all")
+                .about("Solve every registered day and print a timing table")
+                .arg(
+                    clap::Arg::with_name("input_dir")
+                        .long("input-dir")
+                        .takes_value(true)
+                        .default_value("input")
+                        .help("Directory containing `day{N}` puzzle inputs"),
+                )
+                .arg(
+                    clap::Arg::with_name("example")
+                        .long("example")
+                        .short("e")
+                        .help("Use the example inputs `day{N}_ex`"),
+                )
+                .arg(
+                    clap::Arg::with_name("jobs")
+                        .long("jobs")
+                        .short("j")
+                        .takes_value(true)
+                        .default_value("0")
+                        .help("Number of worker threads, 0 for one per CPU"),
+                )
+                .args(&answer_check_args())
+                .args(&record_args())
+                .arg(format_arg()),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("bench")
+                .about("Time repeated runs of a single day/part")
+                .arg(clap::Arg::with_name("day").required(true))
+                .arg(clap::Arg::with_name("part").required(true))
+                .args(&input_args())
+                .arg(param_arg())
+                .arg(
+                    clap::Arg::with_name("runs")
+                        .long("runs")
+                        .short("n")
+                        .takes_value(true)
+                        .default_value("20")
+                        .help("Number of timed runs"),
+                )
+                .arg(
+                    clap::Arg::with_name("warmup")
+                        .long("warmup")
+                        .takes_value(true)
+                        .default_value("3")
+                        .help("Number of untimed runs before measuring"),
+                )
+                .arg(
+                    clap::Arg::with_name("save_baseline")
+                        .long("save-baseline")
+                        .takes_value(true)
+                        .help("Write the results to a baseline JSON file"),
+                )
+                .arg(
+                    clap::Arg::with_name("baseline")
+                        .long("baseline")
+                        .takes_value(true)
+                        .help("Compare the results against a saved baseline"),
+                )
+                .arg(
+                    clap::Arg::with_name("threshold")
+                        .long("threshold")
+                        .takes_value(true)
+                        .default_value("10")
+                        .help("Percent slowdown of the median reported as a regression"),
+                ),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("watch")
+                .about("Re-run a day/part whenever its input (or answers file) changes")
+                .arg(clap::Arg::with_name("day").required(true))
+                .arg(clap::Arg::with_name("part").required(true))
+                .args(&input_args())
+                .arg(param_arg())
+                .arg(
+                    clap::Arg::with_name("answers")
+                        .long("answers")
+                        .takes_value(true)
+                        .help("Answers file to compare against, re-read when it changes"),
+                )
+                .arg(
+                    clap::Arg::with_name("interval")
+                        .long("interval")
+                        .takes_value(true)
+                        .default_value("500")
+                        .help("Milliseconds between polls of the watched files"),
+                ),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("fetch")
+                .about("Download puzzle inputs that are not cached in the input directory yet")
+                .arg(clap::Arg::with_name("day").required(true).multiple(true))
+                .args(&server_args())
+                .arg(
+                    clap::Arg::with_name("input_dir")
+                        .long("input-dir")
+                        .takes_value(true)
+                        .default_value("input")
+                        .help("Directory the inputs are cached in"),
+                ),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("submit")
+                .about("Solve a day/part and submit the answer")
+                .arg(clap::Arg::with_name("day").required(true))
+                .arg(clap::Arg::with_name("part").required(true))
+                .arg(
+                    clap::Arg::with_name("input")
+                        .help("Puzzle input file, or `-` for stdin [default: input/day{N}]"),
+                )
+                .arg(param_arg())
+                .arg(
+                    clap::Arg::with_name("answer")
+                        .long("answer")
+                        .takes_value(true)
+                        .conflicts_with("input")
+                        .help("Submit this answer instead of solving the input"),
+                )
+                .arg(
+                    clap::Arg::with_name("history")
+                        .long("history")
+                        .takes_value(true)
+                        .default_value("submissions.jsonl")
+                        .help("File recording every submitted answer and its reply"),
+                )
+                .args(&server_args()),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("history")
+                .about("Show recorded runs, and how answers and solve times changed")
+                .arg(clap::Arg::with_name("day"))
+                .arg(clap::Arg::with_name("part"))
+                .arg(
+                    clap::Arg::with_name("limit")
+                        .long("limit")
+                        .short("n")
+                        .takes_value(true)
+                        .default_value("10")
+                        .help("Number of most recent runs shown per day/part"),
+                )
+                .arg(runs_arg()),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("generate")
+                .about("Write a random puzzle input, for stress tests and benchmarks")
+                .arg(clap::Arg::with_name("day").required(true))
+                .arg(
+                    clap::Arg::with_name("seed")
+                        .long("seed")
+                        .takes_value(true)
+                        .default_value("0")
+                        .help("Seed for the generator, the same seed gives the same input"),
+                )
+                .arg(
+                    clap::Arg::with_name("size")
+                        .long("size")
+                        .takes_value(true)
+                        .default_value("100")
+                        .help("Number of records: elves, lines, moves, monkey items, packet pairs, sensors..."),
+                )
+                .arg(
+                    clap::Arg::with_name("output")
+                        .long("output")
+                        .short("o")
+                        .takes_value(true)
+                        .help("File to write the input to [default: stdout]"),
+                ),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("repl")
+                .about("Load a day's input once and explore it with commands")
+                .arg(clap::Arg::with_name("day").required(true))
+                .args(&input_args())
+                .arg(param_arg()),
+        )
+        .subcommand(
+            clap::SubCommand::with_name("new-day")
+                .about("Start a new day from the template and register it")
+                .arg(clap::Arg::with_name("day").required(true))
+                .arg(
+                    clap::Arg::with_name("root")
+                        .long("root")
+                        .takes_value(true)
+                        .default_value(".")
+                        .help("Repository root containing `src/` and `input/`"),
+                ),
+        )
         .get_matches()
 }
+
+fn input_args() -> [clap::Arg<'static, 'static>; 2] {
+    [
+        clap::Arg::with_name("input")
+            .help("Puzzle input file, or `-` for stdin [default: input/day{N}]"),
+        clap::Arg::with_name("example")
+            .long("example")
+            .short("e")
+            .conflicts_with("input")
+            .help("Use the example input `input/day{N}_ex`"),
+    ]
+}
+
+fn server_args() -> [clap::Arg<'static, 'static>; 2] {
+    [
+        clap::Arg::with_name("session")
+            .long("session")
+            .takes_value(true)
+            .env("AOC_SESSION")
+            .hide_env_values(true)
+            .help("Session cookie of a logged in adventofcode.com account"),
+        clap::Arg::with_name("base_url")
+            .long("base-url")
+            .takes_value(true)
+            .default_value("https://adventofcode.com")
+            .help("Puzzle website to talk to"),
+    ]
+}
+
+fn param_arg() -> clap::Arg<'static, 'static> {
+    clap::Arg::with_name("param")
+        .long("param")
+        .short("p")
+        .takes_value(true)
+        .multiple(true)
+        .number_of_values(1)
+        .value_name("key=value")
+        .help("Override a puzzle parameter of the day (repeatable)")
+}
+
+fn answer_check_args() -> [clap::Arg<'static, 'static>; 2] {
+    [
+        clap::Arg::with_name("check")
+            .long("check")
+            .help("Verify answers against the answers file"),
+        clap::Arg::with_name("answers")
+            .long("answers")
+            .takes_value(true)
+            .default_value(challenge::DEFAULT_ANSWERS_FILE)
+            .help("Answers file used by --check"),
+    ]
+}
+
+fn runs_arg() -> clap::Arg<'static, 'static> {
+    clap::Arg::with_name("runs")
+        .long("runs")
+        .takes_value(true)
+        .default_value("runs.jsonl")
+        .help("Run log that answers and timings are recorded in")
+}
+
+fn record_args() -> [clap::Arg<'static, 'static>; 2] {
+    [
+        runs_arg(),
+        clap::Arg::with_name("no_record")
+            .long("no-record")
+            .help("Don't add this run to the run log"),
+    ]
+}
+
+fn format_arg() -> clap::Arg<'static, 'static> {
+    clap::Arg::with_name("format")
+        .long("format")
+        .takes_value(true)
+        .possible_values(&["text", "json"])
+        .default_value("text")
+        .help("Output format")
+}
@@ -0,0 +1,31 @@
+//! Replays inputs the fuzz targets once crashed on. Each file in
+//! `fuzz/regressions/dayN/` must be rejected by that day's parser without panicking.
+
+use std::{fs, panic};
+
+use advent_of_code_2022::challenge::fuzz_parse;
+
+#[test]
+fn replay_fuzz_regressions() {
+    let mut replayed = 0;
+    for dir in fs::read_dir("fuzz/regressions").unwrap() {
+        let dir = dir.unwrap().path();
+        let day = dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_prefix("day"))
+            .and_then(|d| d.parse::<u32>().ok())
+            .unwrap_or_else(|| panic!("expected a dayN directory: {}", dir.display()));
+        for case in fs::read_dir(&dir).unwrap() {
+            let case = case.unwrap().path();
+            let data = fs::read(&case).unwrap();
+            let result = panic::catch_unwind(|| fuzz_parse(day, &data));
+            match result {
+                Ok(Err(_)) => replayed += 1,
+                Ok(Ok(())) => panic!("{} was accepted", case.display()),
+                Err(_) => panic!("{} panicked", case.display()),
+            }
+        }
+    }
+    assert!(replayed > 0, "no regressions found");
+}
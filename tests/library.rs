@@ -0,0 +1,33 @@
+use advent_of_code_2022::challenge::{day13, day15, day5, day7, Answer};
+
+#[test]
+fn parse_and_solve_without_the_cli() {
+    let pairs = day13::parse(include_str!("../input/day13_ex")).unwrap();
+    assert_eq!(pairs.len(), 8);
+    assert!(pairs[0].check_order());
+    assert_eq!(day13::solve_p1(&pairs), 13);
+}
+
+#[test]
+fn model_types_are_usable() {
+    let (tower, instructions) = day5::parse::parse(include_str!("../input/day5_ex")).unwrap();
+    assert_eq!(tower.top_of_stack(), "NDP");
+    assert_eq!(instructions[0].count, 1);
+
+    let fs = day7::parse(include_str!("../input/day7_ex")).unwrap();
+    let (root, dirs) = day7::calculate_dir_totals(&fs).unwrap();
+    assert_eq!(root, 48381165);
+    assert!(dirs.iter().all(|(inode, _)| inode.is_dir()));
+
+    let mut mr = day15::MultiRange::default();
+    mr.add_range(0, 4);
+    mr.add_range(3, 9);
+    assert_eq!(mr.count(), 10);
+}
+
+#[test]
+fn registered_solutions() {
+    let day = advent_of_code_2022::challenge::get_solution(13).unwrap();
+    let answer = day.part2(include_str!("../input/day13_ex")).unwrap();
+    assert_eq!(answer, Answer::Int(140));
+}